@@ -1,65 +1,448 @@
-use clap::{Parser, Subcommand};
+use clap::{ArgGroup, Args, Parser, Subcommand, ValueEnum};
+use clap_complete::ArgValueCandidates;
 use std::path::PathBuf;
+use user_registry_lib::{ConflictPolicy, FieldFilter, IdSelector, OutputFormat};
+
+use crate::completions::{complete_ids, complete_profiles};
 
 #[derive(Subcommand, Debug)]
 pub enum Command {
     /// Store a new user entry in the file.
+    ///
+    /// Fields can be given in order as arguments or as named options. When
+    /// run on a terminal with missing fields, every field is prompted for.
     Add {
-        /// The user's first name.
-        first_name: String,
-
-        /// The user's surname (last name).
-        last_name: String,
-
-        /// The user's email address.
-        email: String,
-
-        /// The user's telephone number.
-        phone_number: u64,
+        #[command(flatten)]
+        user: UserArgs,
     },
 
     /// Retrieve a user's data by their unique ID.
     Get {
         /// The ID of the user whose data is to be fetched.
+        #[arg(add = ArgValueCandidates::new(complete_ids))]
         id: usize,
+
+        #[command(flatten)]
+        template: TemplateArgs,
     },
 
-    /// Remove a user entry from the file.
+    /// Remove user entries from the file.
+    ///
+    /// Users are selected by ID, by range of IDs, and by field filters. Only
+    /// users matching the IDs, if any, and every filter are removed. Removing
+    /// more than a single ID asks for confirmation on a terminal, or requires
+    /// --yes.
+    #[command(group(ArgGroup::new("selection").required(true).multiple(true)))]
     Remove {
-        /// The ID of the user to remove.
-        id: usize,
+        /// The IDs of the users to remove, or ranges like 3-7.
+        #[arg(
+            value_name = "ID",
+            group = "selection",
+            add = ArgValueCandidates::new(complete_ids),
+        )]
+        ids: Vec<IdSelector>,
+
+        /// Only remove users whose field matches a pattern, where '*' matches
+        /// any text, e.g. 'email=*@example.com'. Fields are first_name,
+        /// last_name, email and phone_number.
+        #[arg(
+            short = 'w',
+            long = "where",
+            value_name = "FIELD=PATTERN",
+            group = "selection"
+        )]
+        filters: Vec<FieldFilter>,
+
+        /// Remove the users without asking for confirmation.
+        #[arg(short, long)]
+        yes: bool,
+
+        /// List the users that would be removed without removing them.
+        #[arg(long)]
+        dry_run: bool,
     },
 
-    /// Permanently delete all user data.
-    Reset,
+    /// Remove all users, moving the data file to a timestamped backup.
+    Reset {
+        /// Reset without asking for confirmation, even if the data file is
+        /// damaged.
+        #[arg(short, long)]
+        force: bool,
+    },
+
+    /// Check the data file for problems, and repair it if asked to.
+    ///
+    /// Reports syntax errors with their line and column, records that are not
+    /// valid users, and repeated IDs or users. With --repair, every user that
+    /// can be read is saved to a fresh data file, and the damaged one is kept
+    /// as a timestamped backup.
+    Doctor {
+        /// Save the users that can be read to a fresh data file.
+        #[arg(short, long)]
+        repair: bool,
+    },
 
     /// Display all user data in JSON format.
-    Show,
+    Show {
+        #[command(flatten)]
+        template: TemplateArgs,
+    },
+
+    /// Compare the data file with another one and list the differences.
+    Diff {
+        /// The data file to compare against.
+        other: PathBuf,
+
+        /// The format of the differences [default: the configured format, or text].
+        #[arg(short, long, value_enum)]
+        format: Option<DiffFormat>,
+    },
+
+    /// Import the users of another data file into this one.
+    Merge {
+        /// The data file to import users from.
+        other: PathBuf,
+
+        /// How to handle users that conflict with existing ones [default: the
+        /// configured policy, or keep-ours].
+        #[arg(short, long, value_enum)]
+        policy: Option<ConflictPolicy>,
+
+        /// Report what would happen without saving any change.
+        #[arg(long)]
+        dry_run: bool,
+
+        /// The format of the report [default: the configured format, or text].
+        #[arg(short, long, value_enum)]
+        format: Option<OutputFormat>,
+    },
+
+    /// Apply a JSON Patch (RFC 6902) file to the data file, all or nothing.
+    ///
+    /// Paths refer to the document {"users": {"<id>": {"first_name": ...,
+    /// "last_name": ..., "email": ..., "phone_number": ...}}}.
+    Patch {
+        /// The JSON Patch file to apply.
+        file: PathBuf,
+    },
+
+    /// Export all user data for use in other systems.
+    Export {
+        /// The format of the exported data.
+        #[arg(short, long, value_enum, default_value_t = ExportFormat::Ldif)]
+        format: ExportFormat,
+
+        /// The base DN under which LDIF entries are created [default: the
+        /// configured base DN, or ou=people,dc=example,dc=com].
+        #[arg(long, value_name = "DN")]
+        base_dn: Option<String>,
+    },
+
+    /// Run the commands of a script against the data file, all or nothing.
+    ///
+    /// Each line holds a command, written as on the command line (e.g. `add
+    /// Ann Lee ann@example.com 555`) or as JSON (e.g. {"command": "remove",
+    /// "args": [3]}). Empty lines and lines starting with '#' are skipped. If
+    /// a line fails, no changes are saved.
+    Batch {
+        /// The script to run, or '-' to read it from the standard input.
+        #[arg(default_value = "-")]
+        file: PathBuf,
+
+        /// Skip failed lines and save the changes of the others.
+        #[arg(long)]
+        continue_on_error: bool,
+
+        /// The format of the report [default: the configured format, or text].
+        #[arg(short, long, value_enum)]
+        format: Option<OutputFormat>,
+    },
+
+    /// Open an interactive shell that keeps the user data loaded.
+    ///
+    /// The shell accepts the same commands as the program, plus `commit`,
+    /// `rollback` and `exit`.
+    Shell {
+        /// Only save changes on an explicit `commit` instead of after every
+        /// change.
+        #[arg(long)]
+        no_autosave: bool,
+    },
+
+    /// Manage the named profiles of the configuration file.
+    ///
+    /// A profile maps a name to a data file and its options, and is selected
+    /// with --profile.
+    Profile {
+        #[command(subcommand)]
+        command: ProfileCommand,
+    },
+
+    /// Print the script that enables completions in a shell.
+    ///
+    /// Subcommands, options and user IDs are completed. For example, add
+    /// `source <(cli completions bash)` to ~/.bashrc.
+    Completions {
+        /// The shell to enable completions in.
+        #[arg(value_enum)]
+        shell: CompletionShell,
+    },
+
+    /// Open the terminal UI of the application.
+    Tui,
 
     /// Open the GUI of the application.
-    Gui,
+    Gui {
+        /// Show the users without allowing any change to them.
+        #[arg(long)]
+        read_only: bool,
+    },
 }
 
+/// Formats supported by the diff command.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DiffFormat {
+    /// Human-readable text.
+    Text,
+
+    /// JSON, for use by other programs.
+    Json,
+
+    /// A JSON Patch (RFC 6902) that can be applied with the patch command.
+    Patch,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ProfileCommand {
+    /// List the profiles, marking the default one with '*'.
+    List,
+
+    /// Add a profile.
+    Add {
+        /// The name of the profile.
+        name: String,
+
+        /// The data file of the profile.
+        #[arg(short, long, value_name = "FILE")]
+        data: PathBuf,
+
+        /// The format of structured results.
+        #[arg(short, long, value_enum)]
+        format: Option<OutputFormat>,
+
+        /// How to handle users that conflict with existing ones when merging.
+        #[arg(short, long, value_enum)]
+        policy: Option<ConflictPolicy>,
+
+        /// The base DN under which LDIF entries are created.
+        #[arg(long, value_name = "DN")]
+        base_dn: Option<String>,
+    },
+
+    /// Remove a profile.
+    Remove {
+        /// The name of the profile.
+        #[arg(add = ArgValueCandidates::new(complete_profiles))]
+        name: String,
+    },
+
+    /// Print the default profile, or set it.
+    Default {
+        /// The name of the profile to use by default.
+        #[arg(add = ArgValueCandidates::new(complete_profiles))]
+        name: Option<String>,
+
+        /// Stop using a profile by default.
+        #[arg(long, conflicts_with = "name")]
+        unset: bool,
+    },
+}
+
+/// Formats of the errors printed by the program.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ErrorFormat {
+    /// A human-readable message.
+    Text,
+
+    /// A JSON object: {"error": {"kind": ..., "code": ..., "message": ...}}.
+    Json,
+}
+
+/// Shells that completions can be enabled in.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CompletionShell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+/// Formats supported by the export command.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// LDAP Data Interchange Format, as inetOrgPerson entries.
+    Ldif,
+}
+
+/// The fields of a new user, given as positional arguments or named options.
+#[derive(Args, Debug)]
+pub struct UserArgs {
+    /// The user's first name.
+    #[arg(conflicts_with = "named_first_name")]
+    pub first_name: Option<String>,
+
+    /// The user's surname (last name).
+    #[arg(conflicts_with = "named_last_name")]
+    pub last_name: Option<String>,
+
+    /// The user's email address.
+    #[arg(conflicts_with = "named_email")]
+    pub email: Option<String>,
+
+    /// The user's telephone number.
+    #[arg(conflicts_with = "named_phone_number")]
+    pub phone_number: Option<String>,
+
+    /// The user's first name.
+    #[arg(long = "first-name", value_name = "FIRST_NAME")]
+    pub named_first_name: Option<String>,
+
+    /// The user's surname (last name).
+    #[arg(long = "last-name", value_name = "LAST_NAME")]
+    pub named_last_name: Option<String>,
+
+    /// The user's email address.
+    #[arg(long = "email", value_name = "EMAIL")]
+    pub named_email: Option<String>,
+
+    /// The user's telephone number.
+    #[arg(long = "phone", value_name = "PHONE_NUMBER")]
+    pub named_phone_number: Option<String>,
+}
+
+impl UserArgs {
+    /// The named option of every field, in [`Field::ALL`] order.
+    ///
+    /// [`Field::ALL`]: user_registry_lib::Field::ALL
+    pub const OPTIONS: [&str; 4] = ["--first-name", "--last-name", "--email", "--phone"];
+
+    /// Returns the given value of every field, in [`Field::ALL`] order.
+    ///
+    /// [`Field::ALL`]: user_registry_lib::Field::ALL
+    pub fn values(&self) -> [Option<String>; 4] {
+        [
+            self.named_first_name.as_ref().or(self.first_name.as_ref()),
+            self.named_last_name.as_ref().or(self.last_name.as_ref()),
+            self.named_email.as_ref().or(self.email.as_ref()),
+            self.named_phone_number
+                .as_ref()
+                .or(self.phone_number.as_ref()),
+        ]
+        .map(Option::<&String>::cloned)
+    }
+}
+
+/// Options to print users on a single line with a custom format.
+#[derive(Args, Debug)]
+pub struct TemplateArgs {
+    /// Format each user with a template, e.g. '{last_name}, {first_name} <{email}>'.
+    ///
+    /// Available placeholders: {id}, {first_name}, {last_name}, {email} and
+    /// {phone_number}. Append ':csv' or ':shell' to escape a value, as in
+    /// '{email:csv}'. Use '{{' and '}}' for literal braces.
+    #[arg(short, long, value_name = "TEMPLATE", conflicts_with = "template_file")]
+    pub template: Option<String>,
+
+    /// Read the template from a file instead of the command line.
+    #[arg(long, value_name = "FILE")]
+    pub template_file: Option<PathBuf>,
+}
+
+const LONG_HELP: &str = "\
+Configuration:
+  Settings are taken from the first of these that defines them:
+    1. the command-line options;
+    2. the environment variables USER_REGISTRY_DATA, USER_REGISTRY_FORMAT,
+       USER_REGISTRY_POLICY and USER_REGISTRY_BASE_DN;
+    3. the selected profile, a [profiles.<name>] table of the configuration
+       file;
+    4. the keys data, format, policy and base_dn of the configuration file,
+       at USER_REGISTRY_CONFIG or $XDG_CONFIG_HOME/users_registry/config.toml;
+    5. the built-in defaults.
+  The profile is selected by --profile, USER_REGISTRY_PROFILE or the profile
+  key of the configuration file.
+
+Exit codes:
+  0  success
+  1  the terminal UI or the GUI failed (interface)
+  2  invalid arguments or input (invalid_input)
+  3  a user or profile doesn't exist (not_found)
+  4  a data, patch or template file can't be parsed (invalid_file)
+  5  a change doesn't apply, e.g. a failed patch test (conflict)
+  6  reading or writing a file failed (io)
+  7  invalid configuration file or environment variable (config)
+  8  the command isn't available in this build (unavailable)";
+
 /// Program to register users in a file with their data via GUI or CLI.
 #[derive(Parser, Debug)]
-#[command(version, about)]
+#[command(version, about, after_long_help = LONG_HELP)]
 pub struct App {
-    /// File to load and save user data (defaults to data directory).
+    /// File to load and save user data (defaults to the configured file, or
+    /// the data directory).
     #[arg(short, long, value_name = "FILE")]
     pub data: Option<PathBuf>,
 
+    /// Profile of the configuration file to use.
+    #[arg(long, value_name = "NAME", add = ArgValueCandidates::new(complete_profiles))]
+    pub profile: Option<String>,
+
+    /// Format of the errors printed to the standard error.
+    #[arg(
+        long,
+        value_enum,
+        value_name = "FORMAT",
+        default_value_t = ErrorFormat::Text,
+        global = true
+    )]
+    pub error_format: ErrorFormat,
+
     #[command(subcommand)]
     pub command: Command,
 }
 
-impl App {
-    pub fn get_data(&self) -> Option<PathBuf> {
-        self.data.clone().or_else(|| {
-            dirs::data_dir().map(|mut path| {
-                path.push("users_registry");
-                path.push("users.json");
-                path
-            })
-        })
+#[cfg(test)]
+mod tests {
+    use clap::CommandFactory;
+
+    use super::*;
+
+    /// Tests the definition of the command line.
+    ///
+    /// This test ensures that:
+    /// - clap accepts the definition of every argument and subcommand.
+    #[test]
+    fn verify_command_line() {
+        App::command().debug_assert();
+    }
+
+    /// Tests passing the error format anywhere on the command line.
+    ///
+    /// This test ensures that:
+    /// - The error format is read before and after the subcommand, as the
+    ///   errors that happen before parsing expect.
+    /// - Errors are printed as text by default.
+    #[test]
+    fn parse_global_error_format() {
+        for args in [
+            ["cli", "--error-format", "json", "get", "5"],
+            ["cli", "get", "5", "--error-format", "json"],
+        ] {
+            let app = App::try_parse_from(args).unwrap();
+
+            assert_eq!(app.error_format, ErrorFormat::Json);
+            assert!(matches!(app.command, Command::Get { .. }));
+        }
+
+        let app = App::try_parse_from(["cli", "get", "5"]).unwrap();
+        assert_eq!(app.error_format, ErrorFormat::Text);
     }
 }
@@ -0,0 +1,321 @@
+use std::io::{Write, stderr};
+
+use clap::Parser;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use user_registry_lib::{Config, Data, OutputFormat};
+
+use crate::{
+    app::Command,
+    error::Error,
+    execute::{execute, require_arguments, write_json},
+};
+
+/// A line of a batch script.
+#[derive(Parser, Debug)]
+#[command(name = "", no_binary_name = true, disable_version_flag = true)]
+struct BatchLine {
+    #[command(subcommand)]
+    command: Command,
+}
+
+/// A line of a batch script in JSON, e.g.
+/// `{"command": "add", "args": ["Ann", "Lee", "ann@example.com", "555"]}`.
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+struct JsonLine {
+    command: String,
+    #[serde(default)]
+    args: Vec<Value>,
+}
+
+/// The result of a line of a batch script.
+#[derive(Serialize, Debug)]
+struct LineResult {
+    line: usize,
+    ok: bool,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    output: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<Value>,
+}
+
+/// The report of a batch script in JSON.
+#[derive(Serialize, Debug)]
+struct Report {
+    committed: bool,
+    results: Vec<LineResult>,
+}
+
+/// What running a batch script did.
+pub struct Outcome {
+    /// Whether the [`Data`] was modified and must be saved.
+    pub changed: bool,
+
+    /// Whether a kept line reset the registry, so that the data file must be
+    /// backed up before being saved.
+    pub reset: bool,
+
+    /// The error to report if a line failed.
+    pub error: Option<Error>,
+}
+
+/// Splits a line of a batch script into the words of a command.
+fn split_line(line: &str) -> Result<Vec<String>, Error> {
+    if !line.starts_with('{') {
+        return shlex::split(line).ok_or_else(|| {
+            Error::InvalidInput(String::from("The command has unbalanced quotes."))
+        });
+    }
+
+    let line: JsonLine = serde_json::from_str(line)
+        .map_err(|err| Error::InvalidInput(format!("Invalid JSON command: {err}")))?;
+    let mut words = vec![line.command];
+
+    for arg in line.args {
+        words.push(match arg {
+            Value::String(arg) => arg,
+            Value::Number(arg) => arg.to_string(),
+            Value::Bool(arg) => arg.to_string(),
+            arg => {
+                return Err(Error::InvalidInput(format!(
+                    "Invalid JSON command: the argument {arg} is not a string, number or boolean."
+                )));
+            }
+        });
+    }
+
+    Ok(words)
+}
+
+fn parse_line(line: &str) -> Result<Command, Error> {
+    let command = BatchLine::try_parse_from(split_line(line)?)
+        .map_err(|err| Error::from_clap(&err))?
+        .command;
+
+    require_arguments(&command)?;
+    Ok(command)
+}
+
+/// Runs the batch script `script` against `data`, as a single transaction.
+///
+/// Each line holds a command, written as on the command line or as a JSON
+/// object with a `command` name and its `args`. Empty lines and lines starting
+/// with `#` are skipped.
+///
+/// If a line fails, the [`Data`] is left untouched, unless
+/// `continue_on_error` is set: then failed lines are skipped and the others
+/// are kept. In the text `format`, the output of the commands is written to
+/// `writer` and failures to the standard error; in JSON, a report with the
+/// result and output of every line is written to `writer`.
+///
+/// # Errors
+/// This function may return an error if the report can't be written. Errors
+/// of the lines are returned in the [`Outcome`].
+pub fn run<W: Write>(
+    script: &str,
+    data: &mut Data,
+    config: &Config,
+    continue_on_error: bool,
+    format: OutputFormat,
+    writer: &mut W,
+) -> Result<Outcome, Error> {
+    let write_err = |err| Error::from_io("Couldn't write output", err);
+    let mut working = data.clone();
+    let mut changed = false;
+    let mut reset = false;
+    let mut results = Vec::new();
+    let mut failures = Vec::new();
+
+    for (index, line) in script.lines().enumerate() {
+        let line = line.trim();
+
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let mut output = Vec::new();
+        let result = parse_line(line).and_then(|command| {
+            let resets = matches!(command, Command::Reset { .. });
+            execute(command, &mut working, config, &mut output)
+                .map(|changed| (changed, changed && resets))
+        });
+
+        if format == OutputFormat::Text {
+            writer.write_all(&output).map_err(write_err)?;
+        }
+
+        let error = match result {
+            Ok((line_changed, line_reset)) => {
+                changed |= line_changed;
+                reset |= line_reset;
+                None
+            }
+            Err(err) => {
+                if format == OutputFormat::Text {
+                    writeln!(stderr(), "Line {}: {err}", index + 1).map_err(write_err)?;
+                }
+                Some(err)
+            }
+        };
+
+        results.push(LineResult {
+            line: index + 1,
+            ok: error.is_none(),
+            output: String::from_utf8_lossy(&output).into_owned(),
+            error: error.as_ref().map(Error::to_json),
+        });
+
+        if let Some(err) = error {
+            failures.push((index + 1, err));
+
+            if !continue_on_error {
+                break;
+            }
+        }
+    }
+
+    let committed = failures.is_empty() || continue_on_error;
+    let total = results.len();
+
+    if format == OutputFormat::Json {
+        let report = Report { committed, results };
+        write_json(&report, writer).map_err(write_err)?;
+    }
+
+    let error = failures.first().map(|(line, err)| {
+        if committed {
+            err.with_message(format!(
+                "{} of {total} commands failed, starting at line {line}.",
+                failures.len(),
+            ))
+        } else {
+            err.with_message(format!("Line {line} failed, so no changes were saved."))
+        }
+    });
+
+    if !committed {
+        return Ok(Outcome {
+            changed: false,
+            reset: false,
+            error,
+        });
+    }
+
+    *data = working;
+    Ok(Outcome {
+        changed,
+        reset,
+        error,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use user_registry_lib::User;
+
+    use super::*;
+
+    const SCRIPT: &str = r#"
+# Adds two users around a failing line.
+add Ann Lee ann@example.com 555
+get 9
+{"command": "add", "args": ["Bob", "Ray", "bob@example.com", 556]}
+"#;
+
+    /// Helper function to create a [`User`] with data for testing purposes.
+    fn first_user() -> User {
+        User {
+            first_name: String::from("Ann"),
+            last_name: String::from("Lee"),
+            email: String::from("ann@example.com"),
+            phone_number: String::from("555"),
+        }
+    }
+
+    /// Helper function to run a script on a [`Data`] with one [`User`],
+    /// returning the [`Data`], the [`Outcome`] and the JSON report.
+    fn run_script(script: &str, continue_on_error: bool) -> (Data, Outcome, Value) {
+        let mut data = Data::new();
+        data.add_user(first_user());
+
+        let mut writer = Vec::new();
+        let outcome = run(
+            script,
+            &mut data,
+            &Config::default(),
+            continue_on_error,
+            OutputFormat::Json,
+            &mut writer,
+        )
+        .unwrap();
+
+        (data, outcome, serde_json::from_slice(&writer).unwrap())
+    }
+
+    /// Tests that a failing line rolls the whole script back.
+    ///
+    /// This test ensures that:
+    /// - The [`Data`] is left untouched and nothing is to be saved.
+    /// - The script stops at the failing line.
+    /// - The error keeps the kind of the failure and names its line.
+    #[test]
+    fn roll_back_on_error() {
+        let (data, outcome, report) = run_script(SCRIPT, false);
+
+        assert_eq!(data.users(), vec![(0, &first_user())]);
+        assert!(!outcome.changed);
+        assert!(!outcome.reset);
+
+        let error = outcome.error.unwrap();
+        assert_eq!(error.code(), 3);
+        assert_eq!(error.message(), "Line 4 failed, so no changes were saved.");
+
+        assert_eq!(report["committed"], json!(false));
+        assert_eq!(report["results"].as_array().unwrap().len(), 2);
+        assert_eq!(report["results"][1]["line"], json!(4));
+        assert_eq!(report["results"][1]["error"]["kind"], json!("not_found"));
+    }
+
+    /// Tests that failing lines are skipped with `continue_on_error`.
+    ///
+    /// This test ensures that:
+    /// - Comments and blank lines are skipped.
+    /// - The lines that succeed are kept, including JSON ones.
+    /// - The error counts the failures and names the first one.
+    #[test]
+    fn continue_on_error() {
+        let (data, outcome, report) = run_script(SCRIPT, true);
+
+        assert_eq!(data.users().len(), 3);
+        assert_eq!(data.user(2).unwrap().first_name, "Bob");
+        assert!(outcome.changed);
+
+        let error = outcome.error.unwrap();
+        assert_eq!(error.code(), 3);
+        assert_eq!(
+            error.message(),
+            "1 of 3 commands failed, starting at line 4."
+        );
+
+        assert_eq!(report["committed"], json!(true));
+        assert_eq!(report["results"].as_array().unwrap().len(), 3);
+    }
+
+    /// Tests that resets are reported so that the data file is backed up.
+    ///
+    /// This test ensures that:
+    /// - A kept reset is reported in the [`Outcome`].
+    /// - A reset that is rolled back is not.
+    #[test]
+    fn report_resets() {
+        let (data, outcome, _) = run_script("reset --force", false);
+        assert!(data.users().is_empty());
+        assert!(outcome.changed && outcome.reset);
+
+        let (data, outcome, _) = run_script("reset --force\nget 9", false);
+        assert_eq!(data.users().len(), 1);
+        assert!(!outcome.reset);
+    }
+}
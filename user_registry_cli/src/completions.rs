@@ -0,0 +1,115 @@
+use std::{
+    env,
+    io::{self, Write},
+    path::PathBuf,
+};
+
+use clap_complete::{
+    CompletionCandidate,
+    env::{Bash, EnvCompleter, Fish, Zsh},
+};
+use user_registry_lib::{Config, ConfigFile, command::read_data};
+
+use crate::app::CompletionShell;
+
+/// The environment variable that asks the program for completions.
+const COMPLETE_VAR: &str = "COMPLETE";
+
+/// The name of the binary that completions are registered for.
+const BIN_NAME: &str = "cli";
+
+/// Returns the value of the option called `long`, or `short` if it has one,
+/// on the command line being completed.
+fn option_value(long: &str, short: Option<&str>) -> Option<String> {
+    let mut args = env::args().skip_while(|arg| arg != "--").skip(1);
+    let long_prefix = format!("{long}=");
+
+    while let Some(arg) = args.next() {
+        if arg == long || Some(arg.as_str()) == short {
+            return args.next();
+        }
+
+        let short_value = short
+            .and_then(|short| arg.strip_prefix(short))
+            .filter(|value| !value.is_empty());
+
+        if let Some(value) = arg.strip_prefix(&long_prefix).or(short_value) {
+            return Some(value.to_string());
+        }
+    }
+
+    None
+}
+
+/// Returns the data file given with `-d`/`--data` on the command line being
+/// completed, or the one of the configured profile.
+fn data_file() -> Option<PathBuf> {
+    option_value("--data", Some("-d"))
+        .map(PathBuf::from)
+        .or_else(|| {
+            let profile = option_value("--profile", None);
+            Config::load(profile.as_deref()).ok()?.data_file()
+        })
+}
+
+/// Completes the IDs of the stored users, described by their names.
+///
+/// Nothing is completed if the data file can't be read.
+pub fn complete_ids() -> Vec<CompletionCandidate> {
+    let Some(data) = data_file().and_then(|path| read_data(path).ok()) else {
+        return Vec::new();
+    };
+
+    data.users()
+        .into_iter()
+        .map(|(id, user)| {
+            CompletionCandidate::new(id.to_string()).help(Some(
+                format!("{} {}", user.first_name, user.last_name).into(),
+            ))
+        })
+        .collect()
+}
+
+/// Completes the names of the profiles of the configuration file.
+pub fn complete_profiles() -> Vec<CompletionCandidate> {
+    let Ok(file) = ConfigFile::load() else {
+        return Vec::new();
+    };
+
+    file.profiles()
+        .iter()
+        .map(|(name, settings)| {
+            CompletionCandidate::new(name).help(
+                settings
+                    .data
+                    .as_ref()
+                    .map(|data| data.display().to_string().into()),
+            )
+        })
+        .collect()
+}
+
+/// Writes the script that registers completions of the program in `shell`.
+///
+/// The script calls the program back to complete each word, so user IDs are
+/// read from the data file at the time of completion.
+///
+/// # Errors
+/// This function may return an error if the path of the program can't be
+/// found or if writing the script fails.
+pub fn write_registration<W: Write>(shell: CompletionShell, writer: &mut W) -> io::Result<()> {
+    let completer = env::current_exe()?;
+    let shell: &dyn EnvCompleter = match shell {
+        CompletionShell::Bash => &Bash,
+        CompletionShell::Zsh => &Zsh,
+        CompletionShell::Fish => &Fish,
+    };
+
+    shell.write_registration(
+        COMPLETE_VAR,
+        BIN_NAME,
+        BIN_NAME,
+        &completer.to_string_lossy(),
+        writer,
+    )
+}
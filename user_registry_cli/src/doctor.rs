@@ -0,0 +1,75 @@
+use std::{fs, io::Write, path::Path};
+
+use user_registry_lib::{Diagnosis, command::repair};
+
+use crate::error::Error;
+
+/// Checks the data file at `data_file`, writing the problems found to
+/// `writer`, and repairs it if `repair_file` is set and saving the salvaged
+/// users fixes some of them.
+///
+/// # Errors
+/// This function may return an error if the file can't be read or repaired,
+/// or if problems were found and are not repaired.
+pub fn run<W: Write>(data_file: &Path, repair_file: bool, writer: &mut W) -> Result<(), Error> {
+    let write_err = |err| Error::from_io("Couldn't write output", err);
+
+    if !data_file.exists() {
+        writeln!(
+            writer,
+            "{} doesn't exist yet, so there is nothing to check.",
+            data_file.display()
+        )
+        .map_err(write_err)?;
+        return Ok(());
+    }
+
+    let contents = fs::read_to_string(data_file)
+        .map_err(|err| Error::from_io("Couldn't read the data file", err))?;
+    let diagnosis = Diagnosis::check(&contents);
+    let count = |count, noun: &str| match count {
+        1 => format!("1 {noun}"),
+        count => format!("{count} {noun}s"),
+    };
+
+    writeln!(
+        writer,
+        "Checked {}: {} can be read, {} found.",
+        data_file.display(),
+        count(diagnosis.data.users().len(), "user"),
+        count(diagnosis.problems.len(), "problem"),
+    )
+    .map_err(write_err)?;
+
+    for problem in &diagnosis.problems {
+        writeln!(writer, "  {problem}").map_err(write_err)?;
+    }
+
+    if diagnosis.is_healthy() {
+        return Ok(());
+    }
+
+    if !diagnosis.is_repairable() {
+        return Err(Error::InvalidFile(String::from(
+            "The data file has invalid or duplicated users. Edit or remove them to fix it.",
+        )));
+    }
+
+    if !repair_file {
+        return Err(Error::InvalidFile(String::from(
+            "The data file is damaged. Run `cli doctor --repair` to save the users that can be read.",
+        )));
+    }
+
+    let backup = repair(data_file, &diagnosis)
+        .map_err(|err| Error::from_io("Couldn't repair the data file", err))?;
+
+    writeln!(
+        writer,
+        "Saved {} to {}. The damaged file was kept as {}.",
+        count(diagnosis.data.users().len(), "user"),
+        data_file.display(),
+        backup.display()
+    )
+    .map_err(write_err)
+}
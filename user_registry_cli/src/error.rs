@@ -0,0 +1,313 @@
+use std::{
+    error,
+    fmt::{self, Display, Formatter},
+    io,
+    path::Path,
+    process::ExitCode,
+};
+
+use serde_json::{Value, json};
+use user_registry_lib::{ConfigError, DataFileError, PatchError};
+
+use crate::app::ErrorFormat;
+
+/// An error that ends the program, reported with a stable exit code.
+///
+/// Every variant holds the message shown to the user.
+#[derive(Debug)]
+pub enum Error {
+    /// The arguments or the input of a command are invalid. Exit code 2, as
+    /// for command line errors.
+    InvalidInput(String),
+
+    /// A user or a profile doesn't exist. Exit code 3.
+    NotFound(String),
+
+    /// A data, patch or template file can't be parsed. Exit code 4.
+    InvalidFile(String),
+
+    /// A change doesn't apply to the current state, e.g. a patch whose test
+    /// fails or a profile that already exists. Exit code 5.
+    Conflict(String),
+
+    /// Reading or writing a file or the terminal failed. Exit code 6.
+    Io(String),
+
+    /// The configuration file or an environment variable is invalid. Exit
+    /// code 7.
+    Config(String),
+
+    /// The command isn't available in this build or in this context. Exit
+    /// code 8.
+    Unavailable(String),
+
+    /// The terminal UI or the GUI failed. Exit code 1.
+    Interface(String),
+}
+
+impl Error {
+    /// Describes a failed file operation, reporting data that can't be parsed
+    /// as [`Error::InvalidFile`].
+    pub fn from_io(context: &str, err: io::Error) -> Self {
+        let message = format!("{context}: {err}");
+
+        match err.kind() {
+            io::ErrorKind::InvalidData | io::ErrorKind::UnexpectedEof => Self::InvalidFile(message),
+            _ => Self::Io(message),
+        }
+    }
+
+    /// Describes a data file that can't be read, pointing to the `doctor`
+    /// command if it can't be parsed.
+    pub fn from_data(err: io::Error) -> Self {
+        match Self::from_io("Couldn't read users", err) {
+            Self::InvalidFile(message) => Self::InvalidFile(format!(
+                "{message}\nRun `cli doctor` to check the data file, or `cli doctor --repair` to fix it."
+            )),
+            err => err,
+        }
+    }
+
+    /// Describes a command line that clap couldn't parse, keeping only the
+    /// error and not the usage help.
+    pub fn from_clap(err: &clap::Error) -> Self {
+        let rendered = err.render().to_string();
+        let message = rendered.lines().next().unwrap_or_default();
+        let message = message.strip_prefix("error: ").unwrap_or(message);
+
+        Self::InvalidInput(message.to_string())
+    }
+
+    /// Describes another data file that can't be read, e.g. one to compare
+    /// with, reporting contents that can't be parsed as an invalid data file.
+    pub fn from_other_data(path: &Path, err: io::Error) -> Self {
+        match err.kind() {
+            io::ErrorKind::InvalidData | io::ErrorKind::UnexpectedEof => Self::InvalidFile(
+                format!("{} is not a valid data file: {err}", path.display()),
+            ),
+            _ => Self::from_io(&format!("Couldn't read {}", path.display()), err),
+        }
+    }
+
+    /// Describes an error in the configuration.
+    pub fn from_config(context: &str, err: ConfigError) -> Self {
+        let message = format!("{context}: {err}");
+
+        match err {
+            ConfigError::IoError(_) => Self::Io(message),
+            ConfigError::UnknownProfile(_) => Self::NotFound(message),
+            ConfigError::ProfileExists(_) => Self::Conflict(message),
+            ConfigError::Syntax(_)
+            | ConfigError::UnknownKey(_)
+            | ConfigError::InvalidValue { .. } => Self::Config(message),
+        }
+    }
+
+    /// Describes a [`DataFileError`]: a data path that can't be used is
+    /// invalid input.
+    pub fn from_data_file(err: DataFileError) -> Self {
+        let message = err.to_string();
+
+        match err {
+            DataFileError::NoPath => Self::Config(message),
+            DataFileError::NoParent | DataFileError::NotAFile => Self::InvalidInput(message),
+            DataFileError::CreateFolder(err) => {
+                Self::from_io("Failed to create data directory", err)
+            }
+        }
+    }
+
+    /// Describes a [`PatchError`]: patches that don't match the data are
+    /// conflicts, malformed ones are invalid files.
+    pub fn from_patch(context: &str, err: PatchError) -> Self {
+        let message = format!("{context}: {err}");
+
+        match err {
+            PatchError::PathNotFound { .. } | PatchError::TestFailed { .. } => {
+                Self::Conflict(message)
+            }
+            PatchError::InvalidPointer { .. }
+            | PatchError::MoveIntoChild { .. }
+            | PatchError::InvalidData(_) => Self::InvalidFile(message),
+        }
+    }
+
+    /// Returns the name of the kind of error, as reported in JSON.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::InvalidInput(_) => "invalid_input",
+            Self::NotFound(_) => "not_found",
+            Self::InvalidFile(_) => "invalid_file",
+            Self::Conflict(_) => "conflict",
+            Self::Io(_) => "io",
+            Self::Config(_) => "config",
+            Self::Unavailable(_) => "unavailable",
+            Self::Interface(_) => "interface",
+        }
+    }
+
+    /// Returns the exit code of the program for this error.
+    pub fn code(&self) -> u8 {
+        match self {
+            Self::Interface(_) => 1,
+            Self::InvalidInput(_) => 2,
+            Self::NotFound(_) => 3,
+            Self::InvalidFile(_) => 4,
+            Self::Conflict(_) => 5,
+            Self::Io(_) => 6,
+            Self::Config(_) => 7,
+            Self::Unavailable(_) => 8,
+        }
+    }
+
+    /// Returns an error of the same kind with another message.
+    pub fn with_message(&self, message: String) -> Self {
+        match self {
+            Self::InvalidInput(_) => Self::InvalidInput(message),
+            Self::NotFound(_) => Self::NotFound(message),
+            Self::InvalidFile(_) => Self::InvalidFile(message),
+            Self::Conflict(_) => Self::Conflict(message),
+            Self::Io(_) => Self::Io(message),
+            Self::Config(_) => Self::Config(message),
+            Self::Unavailable(_) => Self::Unavailable(message),
+            Self::Interface(_) => Self::Interface(message),
+        }
+    }
+
+    /// Returns the message shown to the user.
+    pub fn message(&self) -> &str {
+        match self {
+            Self::InvalidInput(message)
+            | Self::NotFound(message)
+            | Self::InvalidFile(message)
+            | Self::Conflict(message)
+            | Self::Io(message)
+            | Self::Config(message)
+            | Self::Unavailable(message)
+            | Self::Interface(message) => message,
+        }
+    }
+
+    /// Returns the kind, code and message of the error as a JSON object.
+    pub fn to_json(&self) -> Value {
+        json!({
+            "kind": self.kind(),
+            "code": self.code(),
+            "message": self.message(),
+        })
+    }
+
+    /// Prints the error to the standard error in the given `format` and
+    /// returns the matching [`ExitCode`].
+    pub fn report(&self, format: ErrorFormat) -> ExitCode {
+        match format {
+            ErrorFormat::Text => eprintln!("{self}"),
+            ErrorFormat::Json => eprintln!("{}", json!({ "error": self.to_json() })),
+        }
+
+        ExitCode::from(self.code())
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message())
+    }
+}
+
+impl error::Error for Error {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests the exit codes and JSON kinds of every [`Error`].
+    ///
+    /// This test ensures that:
+    /// - Every kind of error keeps its documented exit code and name, which
+    ///   scripts rely on.
+    /// - Changing the message keeps the kind of the error.
+    #[test]
+    fn stable_codes_and_kinds() {
+        let message = String::from("message");
+        let errors = [
+            (Error::Interface(message.clone()), 1, "interface"),
+            (Error::InvalidInput(message.clone()), 2, "invalid_input"),
+            (Error::NotFound(message.clone()), 3, "not_found"),
+            (Error::InvalidFile(message.clone()), 4, "invalid_file"),
+            (Error::Conflict(message.clone()), 5, "conflict"),
+            (Error::Io(message.clone()), 6, "io"),
+            (Error::Config(message.clone()), 7, "config"),
+            (Error::Unavailable(message.clone()), 8, "unavailable"),
+        ];
+
+        for (error, code, kind) in errors {
+            assert_eq!(error.code(), code);
+            assert_eq!(error.kind(), kind);
+
+            let changed = error.with_message(String::from("other"));
+            assert_eq!(changed.code(), code);
+            assert_eq!(changed.message(), "other");
+        }
+    }
+
+    /// Tests reporting an [`Error`] as JSON.
+    ///
+    /// This test ensures that:
+    /// - The JSON object holds the kind, the code and the message.
+    #[test]
+    fn error_to_json() {
+        let error = Error::NotFound(String::from("The user 3 doesn't exist."));
+
+        assert_eq!(
+            error.to_json(),
+            json!({
+                "kind": "not_found",
+                "code": 3,
+                "message": "The user 3 doesn't exist.",
+            })
+        );
+    }
+
+    /// Tests converting the errors of the library into [`Error`]s.
+    ///
+    /// This test ensures that:
+    /// - Data that can't be parsed is an invalid file, other I/O errors are
+    ///   I/O errors.
+    /// - Unreadable data files point to the doctor command.
+    /// - Configuration errors are mapped to their kinds.
+    /// - Unusable data file paths are invalid input.
+    #[test]
+    fn convert_library_errors() {
+        let invalid = || io::Error::new(io::ErrorKind::InvalidData, "bad JSON");
+
+        assert!(matches!(
+            Error::from_io("Couldn't read", invalid()),
+            Error::InvalidFile(message) if message == "Couldn't read: bad JSON"
+        ));
+        assert!(matches!(
+            Error::from_io("Couldn't read", io::Error::other("denied")),
+            Error::Io(_)
+        ));
+        assert!(matches!(
+            Error::from_data(invalid()),
+            Error::InvalidFile(message) if message.contains("cli doctor")
+        ));
+
+        assert_eq!(
+            Error::from_config("Couldn't load", ConfigError::UnknownProfile(String::new())).code(),
+            3
+        );
+        assert_eq!(
+            Error::from_config("Couldn't load", ConfigError::ProfileExists(String::new())).code(),
+            5
+        );
+        assert_eq!(
+            Error::from_config("Couldn't load", ConfigError::UnknownKey(String::new())).code(),
+            7
+        );
+
+        assert_eq!(Error::from_data_file(DataFileError::NotAFile).code(), 2);
+        assert_eq!(Error::from_data_file(DataFileError::NoPath).code(), 7);
+    }
+}
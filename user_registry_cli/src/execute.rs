@@ -0,0 +1,459 @@
+use std::{
+    fs,
+    io::{self, IsTerminal, Write, stdin, stdout},
+    path::Path,
+};
+
+use serde::Serialize;
+use user_registry_lib::{
+    Config, ConflictPolicy, Data, Diff, Field, FieldFilter, IdSelector, OutputFormat, Patch,
+    Selection, User,
+    command::{
+        Template, back_up,
+        ldif::{DEFAULT_BASE_DN, write_ldif},
+        read_data, show_data, show_data_with_template, write_diff, write_user,
+    },
+};
+
+use crate::{
+    app::{Command, DiffFormat, ExportFormat, TemplateArgs, UserArgs},
+    error::Error,
+    prompt::{confirm, prompt},
+};
+
+pub fn write_json<T: Serialize, W: Write>(value: &T, writer: &mut W) -> Result<(), io::Error> {
+    serde_json::to_writer_pretty(&mut *writer, value)?;
+    writeln!(writer)
+}
+
+/// Fails if some fields of a new user are missing from `values`.
+fn require_values(values: &[Option<String>; 4]) -> Result<(), Error> {
+    let missing = UserArgs::OPTIONS
+        .into_iter()
+        .zip(values)
+        .filter(|(_, value)| value.is_none())
+        .map(|(option, _)| option)
+        .collect::<Vec<_>>();
+
+    if missing.is_empty() {
+        return Ok(());
+    }
+
+    Err(Error::InvalidInput(format!(
+        "Missing user fields: {}. Pass them as arguments or run on a terminal to be prompted.",
+        missing.join(", ")
+    )))
+}
+
+/// Returns whether removing users needs a confirmation, which is the case
+/// unless a single ID is given.
+fn is_bulk_remove(ids: &[IdSelector], filters: &[FieldFilter]) -> bool {
+    !matches!(ids, [IdSelector::Single(_)]) || !filters.is_empty()
+}
+
+/// Fails if a [`Command`] would prompt for input, so it can run unattended.
+pub fn require_arguments(command: &Command) -> Result<(), Error> {
+    match command {
+        Command::Add { user } => require_values(&user.values()),
+        Command::Remove {
+            ids,
+            filters,
+            yes: false,
+            dry_run: false,
+        } if is_bulk_remove(ids, filters) => Err(Error::InvalidInput(String::from(
+            "Removing several users needs a confirmation. Pass --yes to confirm.",
+        ))),
+        Command::Reset { force: false } => Err(Error::InvalidInput(String::from(
+            "Resetting the registry needs a confirmation. Pass --force to confirm.",
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// Removes the selected users, after listing them and asking for
+/// confirmation if several users could be selected.
+fn remove_users<W: Write>(
+    ids: Vec<IdSelector>,
+    filters: Vec<FieldFilter>,
+    yes: bool,
+    dry_run: bool,
+    data: &mut Data,
+    writer: &mut W,
+) -> Result<bool, Error> {
+    let write_err = |err| Error::from_io("Couldn't write output", err);
+    let bulk = is_bulk_remove(&ids, &filters);
+
+    let missing: Vec<String> = ids
+        .iter()
+        .filter_map(|ids| match ids {
+            IdSelector::Single(id) if data.user(*id).is_none() => Some(id.to_string()),
+            _ => None,
+        })
+        .collect();
+
+    match missing.as_slice() {
+        [] => {}
+        [id] => {
+            return Err(Error::NotFound(format!(
+                "Couldn't remove user: The user with the ID {id} was not found."
+            )));
+        }
+        ids => {
+            return Err(Error::NotFound(format!(
+                "Couldn't remove users: The users with the IDs {} were not found.",
+                ids.join(", ")
+            )));
+        }
+    }
+
+    let selected = data.select(&Selection { ids, filters });
+
+    if selected.is_empty() {
+        return Err(Error::NotFound(String::from(
+            "Couldn't remove users: No user matches the selection.",
+        )));
+    }
+
+    if bulk || dry_run {
+        for id in &selected {
+            if let Some(user) = data.user(*id) {
+                writeln!(
+                    writer,
+                    "  {id}: {} {} <{}>",
+                    user.first_name, user.last_name, user.email
+                )
+                .map_err(write_err)?;
+            }
+        }
+    }
+
+    let count = match selected.len() {
+        1 => String::from("1 user"),
+        count => format!("{count} users"),
+    };
+
+    if dry_run {
+        writeln!(writer, "Dry run: no changes were saved.").map_err(write_err)?;
+        return Ok(false);
+    }
+
+    if bulk && !yes {
+        if !stdin().is_terminal() {
+            return Err(Error::InvalidInput(String::from(
+                "Removing several users needs a confirmation. Pass --yes to confirm.",
+            )));
+        }
+
+        let question = format!("Remove {count}?");
+        let confirmed = confirm(&mut stdin().lock(), writer, &question)
+            .map_err(|err| Error::Io(format!("Couldn't read the confirmation: {err}")))?;
+
+        if !confirmed {
+            writeln!(writer, "No users were removed.").map_err(write_err)?;
+            return Ok(false);
+        }
+    }
+
+    for id in &selected {
+        data.remove_user(*id);
+    }
+
+    if bulk {
+        writeln!(writer, "Removed {count}.").map_err(write_err)?;
+    }
+
+    Ok(true)
+}
+
+/// Asks whether to reset the registry and its `count` users, unless `force`
+/// is set.
+///
+/// # Errors
+/// This function may return an error if the confirmation is needed but the
+/// standard input is not a terminal, or if it can't be read.
+pub fn confirm_reset<W: Write>(count: usize, force: bool, writer: &mut W) -> Result<bool, Error> {
+    if force {
+        return Ok(true);
+    }
+
+    if !stdin().is_terminal() {
+        return Err(Error::InvalidInput(String::from(
+            "Resetting the registry needs a confirmation. Pass --force to confirm.",
+        )));
+    }
+
+    let question = match count {
+        1 => String::from("Reset the registry and remove its user?"),
+        count => format!("Reset the registry and remove its {count} users?"),
+    };
+    let confirmed = confirm(&mut stdin().lock(), writer, &question)
+        .map_err(|err| Error::Io(format!("Couldn't read the confirmation: {err}")))?;
+
+    if !confirmed {
+        writeln!(writer, "The registry was not reset.")
+            .map_err(|err| Error::from_io("Couldn't write output", err))?;
+    }
+
+    Ok(confirmed)
+}
+
+/// Copies the data file at `data_file` to a timestamped backup before the
+/// users of a reset registry are saved over it, telling where to `writer`.
+///
+/// # Errors
+/// This function may return an error if the backup can't be made, in which
+/// case the data file must not be saved.
+pub fn back_up_reset<W: Write>(data_file: &Path, writer: &mut W) -> Result<(), Error> {
+    let backup =
+        back_up(data_file).map_err(|err| Error::from_io("Couldn't back up the data file", err))?;
+
+    if let Some(backup) = backup {
+        writeln!(writer, "The old users were copied to {}.", backup.display())
+            .map_err(|err| Error::from_io("Couldn't write output", err))?;
+    }
+
+    Ok(())
+}
+
+/// Removes all users from `data`, after asking for confirmation unless
+/// `force` is set.
+fn reset_users<W: Write>(force: bool, data: &mut Data, writer: &mut W) -> Result<bool, Error> {
+    let count = data.users().len();
+
+    if count == 0 {
+        writeln!(writer, "The registry is already empty.")
+            .map_err(|err| Error::from_io("Couldn't write output", err))?;
+        return Ok(false);
+    }
+
+    Ok(confirm_reset(count, force, writer)? && data.reset())
+}
+
+/// Reads the data file `other` that the current one is compared or merged
+/// with, failing if it doesn't exist rather than reading it as an empty
+/// registry.
+fn read_other(other: &Path) -> Result<Data, Error> {
+    if !other.is_file() {
+        return Err(Error::NotFound(format!(
+            "The data file {} doesn't exist.",
+            other.display()
+        )));
+    }
+
+    read_data(other).map_err(|err| Error::from_other_data(other, err))
+}
+
+fn read_user(args: &UserArgs) -> Result<User, Error> {
+    let mut values = args.values();
+
+    if values.iter().any(Option::is_none) {
+        if !stdin().is_terminal() {
+            require_values(&values)?;
+        }
+
+        let mut reader = stdin().lock();
+        let mut writer = stdout();
+
+        for (field, value) in Field::ALL.into_iter().zip(&mut values) {
+            let input = prompt(&mut reader, &mut writer, field, value.as_deref())
+                .map_err(|err| Error::Io(format!("Couldn't read {}: {err}", field.name())))?;
+            *value = Some(input);
+        }
+    }
+
+    let [first_name, last_name, email, phone_number] = values.map(Option::unwrap_or_default);
+    let user = User {
+        first_name,
+        last_name,
+        email,
+        phone_number,
+    };
+
+    user.validate()
+        .map_err(|err| Error::InvalidInput(format!("Invalid user: {err}")))?;
+    Ok(user)
+}
+
+fn read_template(args: TemplateArgs) -> Result<Option<Template>, Error> {
+    let source = match (args.template, args.template_file) {
+        (Some(template), _) => template,
+        (None, Some(path)) => fs::read_to_string(&path)
+            .map(|template| template.trim_end_matches(['\r', '\n']).to_string())
+            .map_err(|err| Error::from_io("Couldn't read template file", err))?,
+        (None, None) => return Ok(None),
+    };
+
+    Template::parse(&source)
+        .map(Some)
+        .map_err(|err| Error::InvalidInput(format!("Invalid template: {err}")))
+}
+
+/// Runs a [`Command`] against an already loaded [`Data`], writing its output
+/// to `writer`.
+///
+/// Options left out of the command are taken from `config`. Returns whether
+/// the [`Data`] was modified, so the caller can decide when to save it.
+/// Commands that don't operate on a [`Data`], such as opening the GUI, are
+/// rejected.
+pub fn execute<W: Write>(
+    command: Command,
+    data: &mut Data,
+    config: &Config,
+    writer: &mut W,
+) -> Result<bool, Error> {
+    let write_err = |err| Error::from_io("Couldn't write output", err);
+
+    match command {
+        Command::Add { user } => {
+            let user = read_user(&user)?;
+            data.add_user(user);
+            Ok(true)
+        }
+        Command::Get { id, template } => {
+            let template = read_template(template)?;
+            let user = data.user(id).ok_or(Error::NotFound(format!(
+                "Couldn't get user: The user with the ID {id} was not found."
+            )))?;
+
+            match template {
+                Some(template) => template.write_user(user, id, writer),
+                None => write_user(user, id, writer),
+            }
+            .map_err(write_err)?;
+
+            Ok(false)
+        }
+        Command::Remove {
+            ids,
+            filters,
+            yes,
+            dry_run,
+        } => remove_users(ids, filters, yes, dry_run, data, writer),
+        Command::Reset { force } => reset_users(force, data, writer),
+        Command::Show { template } => {
+            match read_template(template)? {
+                Some(template) => show_data_with_template(data, &template, writer),
+                None => show_data(data, writer),
+            }
+            .map_err(write_err)?;
+
+            Ok(false)
+        }
+        Command::Diff { other, format } => {
+            let other = read_other(&other)?;
+            let diff = Diff::new(data, &other);
+            let format = format.unwrap_or(match config.format {
+                Some(OutputFormat::Json) => DiffFormat::Json,
+                Some(OutputFormat::Text) | None => DiffFormat::Text,
+            });
+
+            match format {
+                DiffFormat::Text => write_diff(&diff, writer),
+                DiffFormat::Json => write_json(&diff, writer),
+                DiffFormat::Patch => write_json(&diff.to_patch(), writer),
+            }
+            .map_err(write_err)?;
+
+            Ok(false)
+        }
+        Command::Patch { file } => {
+            let patch: Patch = fs::read_to_string(file)
+                .and_then(|contents| Ok(serde_json::from_str(&contents)?))
+                .map_err(|err| Error::from_io("Couldn't read patch", err))?;
+
+            data.apply_patch(&patch)
+                .map_err(|err| Error::from_patch("Couldn't apply patch", err))?;
+            Ok(true)
+        }
+        Command::Merge {
+            other,
+            policy,
+            dry_run,
+            format,
+        } => {
+            let other = read_other(&other)?;
+            let mut merged = data.clone();
+            let policy = policy.or(config.policy).unwrap_or(ConflictPolicy::KeepOurs);
+            let records = merged.merge(&other, policy);
+
+            match format.or(config.format).unwrap_or(OutputFormat::Text) {
+                OutputFormat::Text => {
+                    for record in &records {
+                        writeln!(writer, "{record}").map_err(write_err)?;
+                    }
+                    if dry_run {
+                        writeln!(writer, "Dry run: no changes were saved.").map_err(write_err)?;
+                    }
+                }
+                OutputFormat::Json => write_json(&records, writer).map_err(write_err)?,
+            }
+
+            if dry_run {
+                return Ok(false);
+            }
+
+            *data = merged;
+            Ok(true)
+        }
+        Command::Export { format, base_dn } => {
+            let base_dn = base_dn
+                .or_else(|| config.base_dn.clone())
+                .unwrap_or_else(|| DEFAULT_BASE_DN.to_string());
+
+            match format {
+                ExportFormat::Ldif => write_ldif(data, &base_dn, writer),
+            }
+            .map_err(write_err)?;
+
+            Ok(false)
+        }
+        Command::Batch { .. }
+        | Command::Doctor { .. }
+        | Command::Gui { .. }
+        | Command::Tui
+        | Command::Shell { .. }
+        | Command::Completions { .. }
+        | Command::Profile { .. } => Err(Error::Unavailable(String::from(
+            "This command is not available here.",
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use super::*;
+
+    /// Tests reading the data file that the current one is compared or
+    /// merged with.
+    ///
+    /// This test ensures that:
+    /// - A missing file or a folder is not found, instead of being read as
+    ///   an empty registry.
+    /// - A file that can't be parsed is an invalid file.
+    /// - An empty file is an empty registry.
+    #[test]
+    fn read_other_data_file() {
+        let folder = env::temp_dir().join("users_registry_read_other_test");
+        fs::create_dir_all(&folder).unwrap();
+
+        assert_eq!(
+            read_other(&folder.join("missing.json")).unwrap_err().code(),
+            3
+        );
+        assert_eq!(read_other(&folder).unwrap_err().code(), 3);
+
+        let path = folder.join("invalid.json");
+        fs::write(&path, "{\"0\": ").unwrap();
+        let error = read_other(&path).unwrap_err();
+        assert_eq!(error.code(), 4);
+        assert!(error.message().contains("is not a valid data file"));
+
+        fs::write(&path, "").unwrap();
+        let data = read_other(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert!(data.users().is_empty());
+    }
+}
@@ -1,76 +1,195 @@
-use std::{fs, io::stdout};
+use std::{
+    env, fs,
+    io::{Read, stdin, stdout},
+    process::ExitCode,
+};
 
-use app::{App, Command};
-use clap::Parser;
+use app::{App, Command, ErrorFormat};
+use clap::{CommandFactory, Parser};
+use clap_complete::CompleteEnv;
+use execute::{back_up_reset, confirm_reset, execute};
 use user_registry_lib::{
-    User,
-    command::{add, get, remove, reset, show, write_user},
+    Config, OutputFormat,
+    command::{force_reset, read_data, reset, save_data},
 };
 
+pub use error::Error;
+
 mod app;
+mod batch;
+mod completions;
+mod doctor;
+mod error;
+mod execute;
+mod profile;
+mod prompt;
+mod shell;
+#[cfg(feature = "tui")]
+mod tui;
 
-pub fn run() -> Result<(), String> {
-    let app = App::parse();
-    let data_file = app.get_data().ok_or(String::from(
-        "Couldn't get data file path. Try using --data to specify one.",
-    ))?;
+/// Returns the error format asked for on the command line, for errors that
+/// happen before it is parsed.
+fn requested_error_format() -> ErrorFormat {
+    let mut args = env::args().skip(1);
 
-    let Some(parent) = data_file.parent() else {
-        return Err(String::from("Couldn't get data file parent."));
-    };
+    while let Some(arg) = args.next() {
+        let value = match arg.strip_prefix("--error-format=") {
+            Some(value) => Some(value.to_string()),
+            None if arg == "--error-format" => args.next(),
+            None => continue,
+        };
+
+        if value.as_deref() == Some("json") {
+            return ErrorFormat::Json;
+        }
+    }
+
+    ErrorFormat::Text
+}
+
+fn run_app(app: App) -> Result<(), Error> {
+    if let Command::Completions { shell } = app.command {
+        return completions::write_registration(shell, &mut stdout())
+            .map_err(|err| Error::from_io("Couldn't write completions", err));
+    }
 
-    if let Err(err) = fs::create_dir_all(parent) {
-        return Err(format!("Failed to create data directory: {err}"));
+    if let Command::Profile { command } = app.command {
+        return profile::run(command, &mut stdout());
     }
 
-    if data_file.exists() && !data_file.is_file() {
-        return Err(String::from(
-            "The data path must be a file. Specify another one.",
-        ));
+    let mut config = Config::load(app.profile.as_deref())
+        .map_err(|err| Error::from_config("Couldn't load configuration", err))?;
+    // The data file given on the command line belongs to no profile.
+    if app.data.is_some() {
+        config.profile = None;
     }
 
+    let data_file = match app.command {
+        Command::Gui { read_only: true } => config.find_data_file(app.data),
+        _ => config.prepare_data_file(app.data),
+    }
+    .map_err(Error::from_data_file)?;
+
     match app.command {
-        Command::Add {
-            first_name,
-            last_name,
-            email,
-            phone_number,
-        } => {
-            let user = User {
-                first_name,
-                last_name,
-                email,
-                phone_number: phone_number.to_string(),
-            };
-
-            add(data_file, user).map_err(|err| format!("User couldn't be added: {err}"))?;
-        }
-        Command::Get { id } => {
-            let user = get(data_file, id).map_err(|err| format!("Couldn't get user: {err}"))?;
-            write_user(&user, id, &mut stdout())
-                .map_err(|err| format!("Couldn't write user: {err}"))?;
-        }
-        Command::Remove { id } => {
-            remove(data_file, id).map_err(|err| format!("Couldn't remove user: {err}"))?;
+        Command::Doctor { repair } => doctor::run(&data_file, repair, &mut stdout())?,
+        Command::Reset { force } => {
+            // A damaged data file is the main reason to reset, so it isn't
+            // read when the reset needs no confirmation.
+            let backup = if force {
+                force_reset(&data_file)
+            } else {
+                let data = read_data(&data_file).map_err(Error::from_data)?;
+                let count = data.users().len();
+
+                if count > 0 && !confirm_reset(count, false, &mut stdout())? {
+                    return Ok(());
+                }
+
+                reset(&data_file)
+            }
+            .map_err(|err| Error::from_io("Couldn't reset the data file", err))?;
+
+            match backup {
+                Some(backup) => println!("The old users were moved to {}.", backup.display()),
+                None => println!("The registry is already empty."),
+            }
         }
-        Command::Reset => {
-            reset(data_file).map_err(|err| format!("Couldn't reset the data file: {err}"))?;
+        Command::Batch {
+            file,
+            continue_on_error,
+            format,
+        } => {
+            let script = if file.as_os_str() == "-" {
+                let mut script = String::new();
+                stdin().read_to_string(&mut script).map(|_| script)
+            } else {
+                fs::read_to_string(&file)
+            }
+            .map_err(|err| Error::from_io("Couldn't read the script", err))?;
+
+            let mut data = read_data(&data_file).map_err(Error::from_data)?;
+            let format = format.or(config.format).unwrap_or(OutputFormat::Text);
+            let outcome = batch::run(
+                &script,
+                &mut data,
+                &config,
+                continue_on_error,
+                format,
+                &mut stdout(),
+            )?;
+
+            if outcome.reset {
+                back_up_reset(&data_file, &mut stdout())?;
+            }
+
+            if outcome.changed {
+                save_data(&data_file, &data)
+                    .map_err(|err| Error::from_io("Couldn't save users", err))?;
+            }
+
+            if let Some(err) = outcome.error {
+                return Err(err);
+            }
         }
-        Command::Show => {
-            show(data_file, &mut stdout()).map_err(|err| format!("Couldn't write users: {err}"))?;
+        Command::Shell { no_autosave } => shell::run(&data_file, &config, !no_autosave)?,
+        Command::Tui => {
+            #[cfg(not(feature = "tui"))]
+            return Err(Error::Unavailable(String::from(
+                "The 'tui' feature is disabled. To enable it, recompile the program with the flag `--features tui`.",
+            )));
+
+            #[cfg(feature = "tui")]
+            tui::run(&data_file)?;
         }
-        Command::Gui => {
+        #[cfg_attr(not(feature = "gui"), allow(unused_variables))]
+        Command::Gui { read_only } => {
             #[cfg(not(feature = "gui"))]
-            return Err(String::from(
+            return Err(Error::Unavailable(String::from(
                 "The 'gui' feature is disabled. To enable it, recompile the program with the flag `--features gui`.",
-            ));
+            )));
 
             #[cfg(feature = "gui")]
-            if let Err(err) = user_registry_gui::run(&data_file) {
-                return Err(format!("An error occurred in the GUI: {err}"));
+            if let Err(err) = user_registry_gui::run(&data_file, config.profile, read_only) {
+                return Err(Error::Interface(format!(
+                    "An error occurred in the GUI: {err}"
+                )));
+            }
+        }
+        command => {
+            let mut data = read_data(&data_file).map_err(Error::from_data)?;
+
+            if execute(command, &mut data, &config, &mut stdout())? {
+                save_data(&data_file, &data)
+                    .map_err(|err| Error::from_io("Couldn't save users", err))?;
             }
         }
     }
 
     Ok(())
 }
+
+/// Runs the program with the arguments it was started with.
+///
+/// Errors are printed to the standard error in the format chosen with
+/// `--error-format`, and reported by the returned [`ExitCode`], as documented
+/// on [`Error`].
+pub fn run() -> ExitCode {
+    CompleteEnv::with_factory(App::command).complete();
+
+    let app = match App::try_parse() {
+        Ok(app) => app,
+        // Help and version requests are reported as errors by clap.
+        Err(err) if !err.use_stderr() => err.exit(),
+        Err(err) => match requested_error_format() {
+            ErrorFormat::Text => err.exit(),
+            format => return Error::from_clap(&err).report(format),
+        },
+    };
+
+    let format = app.error_format;
+
+    match run_app(app) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => err.report(format),
+    }
+}
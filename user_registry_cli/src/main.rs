@@ -1,10 +1,7 @@
-use std::process;
+use std::process::ExitCode;
 
 use user_registry_cli::run;
 
-fn main() {
-    if let Err(err) = run() {
-        eprintln!("{err}");
-        process::exit(1);
-    }
+fn main() -> ExitCode {
+    run()
 }
@@ -0,0 +1,98 @@
+use std::{
+    io::{self, Write},
+    path,
+};
+
+use user_registry_lib::{Config, ConfigFile};
+
+use crate::{app::ProfileCommand, error::Error};
+
+fn write_profiles<W: Write>(file: &ConfigFile, writer: &mut W) -> Result<(), io::Error> {
+    if file.profiles().is_empty() {
+        return writeln!(writer, "No profiles.");
+    }
+
+    let default = file.settings().profile.as_deref();
+    let width = file.profiles().keys().map(String::len).max().unwrap_or(0);
+
+    for (name, settings) in file.profiles() {
+        let marker = if Some(name.as_str()) == default {
+            '*'
+        } else {
+            ' '
+        };
+        let data = settings.data.as_ref().map_or_else(
+            || String::from("(default data file)"),
+            |data| data.display().to_string(),
+        );
+
+        writeln!(writer, "{marker} {name:width$}  {data}")?;
+    }
+
+    Ok(())
+}
+
+/// Runs a [`ProfileCommand`] against the configuration file, writing its
+/// output to `writer`.
+pub fn run<W: Write>(command: ProfileCommand, writer: &mut W) -> Result<(), Error> {
+    let path = Config::path().ok_or(Error::Config(String::from(
+        "Couldn't get configuration file path. Try using USER_REGISTRY_CONFIG to specify one.",
+    )))?;
+    let mut file = ConfigFile::read(&path)
+        .map_err(|err| Error::from_config("Couldn't load configuration", err))?;
+    let write_err = |err| Error::from_io("Couldn't write output", err);
+
+    let message = match command {
+        ProfileCommand::List => {
+            return write_profiles(&file, writer).map_err(write_err);
+        }
+        ProfileCommand::Add {
+            name,
+            data,
+            format,
+            policy,
+            base_dn,
+        } => {
+            // Relative paths would otherwise depend on the working directory
+            // of every later call.
+            let data = path::absolute(data)
+                .map_err(|err| Error::from_io("Couldn't resolve the data file path", err))?;
+            let settings = Config {
+                data: Some(data),
+                format,
+                policy,
+                base_dn,
+                ..Config::default()
+            };
+
+            file.add_profile(&name, settings)
+                .map_err(|err| Error::from_config("Couldn't add profile", err))?;
+            format!("Added profile '{name}'.")
+        }
+        ProfileCommand::Remove { name } => {
+            file.remove_profile(&name)
+                .map_err(|err| Error::from_config("Couldn't remove profile", err))?;
+            format!("Removed profile '{name}'.")
+        }
+        ProfileCommand::Default { name: None, unset } if !unset => {
+            return match &file.settings().profile {
+                Some(name) => writeln!(writer, "{name}"),
+                None => writeln!(writer, "No default profile."),
+            }
+            .map_err(write_err);
+        }
+        ProfileCommand::Default { name, .. } => {
+            file.set_default_profile(name.as_deref())
+                .map_err(|err| Error::from_config("Couldn't set the default profile", err))?;
+
+            match name {
+                Some(name) => format!("The default profile is now '{name}'."),
+                None => String::from("No profile is used by default anymore."),
+            }
+        }
+    };
+
+    file.write(&path)
+        .map_err(|err| Error::from_io("Couldn't save configuration", err))?;
+    writeln!(writer, "{message}").map_err(write_err)
+}
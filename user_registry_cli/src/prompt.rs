@@ -0,0 +1,128 @@
+use std::io::{self, BufRead, ErrorKind, Write};
+
+use user_registry_lib::Field;
+
+/// Reads a line from `reader`, failing if the input has ended.
+fn read_line<R: BufRead>(reader: &mut R) -> Result<String, io::Error> {
+    let mut line = String::new();
+
+    if reader.read_line(&mut line)? == 0 {
+        return Err(io::Error::new(ErrorKind::UnexpectedEof, "input ended"));
+    }
+
+    Ok(line.trim().to_string())
+}
+
+/// Asks a yes or no `question`, where anything but yes means no.
+pub fn confirm<R: BufRead, W: Write>(
+    reader: &mut R,
+    writer: &mut W,
+    question: &str,
+) -> Result<bool, io::Error> {
+    write!(writer, "{question} [y/N]: ")?;
+    writer.flush()?;
+
+    let answer = read_line(reader)?.to_lowercase();
+    Ok(answer == "y" || answer == "yes")
+}
+
+/// Asks for the value of a user [`Field`] until a valid one is entered.
+///
+/// If a `default` is given, it is shown between brackets and used when the
+/// input is empty. Invalid values are reported and asked for again.
+pub fn prompt<R: BufRead, W: Write>(
+    reader: &mut R,
+    writer: &mut W,
+    field: Field,
+    default: Option<&str>,
+) -> Result<String, io::Error> {
+    loop {
+        match default {
+            Some(default) => write!(writer, "{field} [{default}]: ")?,
+            None => write!(writer, "{field}: ")?,
+        }
+        writer.flush()?;
+
+        let input = read_line(reader)?;
+        let value = match default {
+            Some(default) if input.is_empty() => default.to_string(),
+            _ => input,
+        };
+
+        match field.validate(&value) {
+            Ok(()) => return Ok(value),
+            Err(err) => writeln!(writer, "  {err}")?,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests asking a yes or no question.
+    ///
+    /// This test ensures that:
+    /// - The question is written with the possible answers.
+    /// - Yes is accepted in any case, with or without surrounding spaces.
+    /// - Any other answer, including an empty one, means no.
+    /// - Input that ends before an answer is an error.
+    #[test]
+    fn confirm_answers() {
+        for (input, expected) in [
+            ("y\n", true),
+            (" YES \n", true),
+            ("n\n", false),
+            ("\n", false),
+        ] {
+            let mut writer = Vec::new();
+            let answer = confirm(&mut input.as_bytes(), &mut writer, "Reset?").unwrap();
+
+            assert_eq!(answer, expected, "answer {input:?}");
+            assert_eq!(String::from_utf8(writer).unwrap(), "Reset? [y/N]: ");
+        }
+
+        let err = confirm(&mut "".as_bytes(), &mut Vec::new(), "Reset?").unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+    }
+
+    /// Tests prompting for the value of a [`Field`].
+    ///
+    /// This test ensures that:
+    /// - Invalid values are reported and asked for again.
+    /// - The first valid value is returned, trimmed.
+    /// - The default is shown and used for empty input.
+    /// - Input that ends before a valid value is an error.
+    #[test]
+    fn prompt_until_valid() {
+        let mut writer = Vec::new();
+        let value = prompt(
+            &mut "ann.example.com\n ann@example.com \n".as_bytes(),
+            &mut writer,
+            Field::Email,
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(value, "ann@example.com");
+        assert_eq!(
+            String::from_utf8(writer).unwrap(),
+            "Email:   Email must look like name@domain.\nEmail: "
+        );
+
+        let mut writer = Vec::new();
+        let value = prompt(
+            &mut "\n".as_bytes(),
+            &mut writer,
+            Field::FirstName,
+            Some("Ann"),
+        )
+        .unwrap();
+
+        assert_eq!(value, "Ann");
+        assert_eq!(String::from_utf8(writer).unwrap(), "First name [Ann]: ");
+
+        let err = prompt(&mut "\n".as_bytes(), &mut Vec::new(), Field::LastName, None).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+    }
+}
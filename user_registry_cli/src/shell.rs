@@ -0,0 +1,260 @@
+use std::{io::stdout, path::Path};
+
+use clap::{CommandFactory, Parser, Subcommand};
+use rustyline::{
+    Context, Editor, Helper, completion::Completer, error::ReadlineError, highlight::Highlighter,
+    hint::Hinter, history::DefaultHistory, validate::Validator,
+};
+use user_registry_lib::{
+    Config, Data,
+    command::{read_data, save_data},
+};
+
+use crate::{
+    app::Command,
+    error::Error,
+    execute::{back_up_reset, execute},
+};
+
+const PROMPT: &str = "users> ";
+
+/// A line entered in the shell.
+#[derive(Parser, Debug)]
+#[command(name = "", no_binary_name = true, disable_version_flag = true)]
+struct ShellLine {
+    #[command(subcommand)]
+    command: ShellCommand,
+}
+
+#[derive(Subcommand, Debug)]
+enum ShellCommand {
+    #[command(flatten)]
+    Registry(Command),
+
+    /// Save pending changes to the data file.
+    Commit,
+
+    /// Discard pending changes and reload the data file.
+    Rollback,
+
+    /// Leave the shell.
+    #[command(visible_alias = "quit")]
+    Exit {
+        /// Leave even if there are unsaved changes.
+        #[arg(long)]
+        discard: bool,
+    },
+}
+
+/// Completes command names, their options and user IDs.
+struct ShellHelper {
+    commands: Vec<(String, Vec<String>)>,
+    ids: Vec<String>,
+}
+
+impl ShellHelper {
+    fn new() -> Self {
+        let commands = ShellLine::command()
+            .get_subcommands()
+            .map(|command| {
+                let options = command
+                    .get_arguments()
+                    .filter_map(|arg| arg.get_long())
+                    .map(|long| format!("--{long}"))
+                    .collect();
+
+                (command.get_name().to_string(), options)
+            })
+            .collect();
+
+        Self {
+            commands,
+            ids: Vec::new(),
+        }
+    }
+
+    fn update_ids(&mut self, data: &Data) {
+        self.ids = data
+            .users()
+            .into_iter()
+            .map(|(id, _)| id.to_string())
+            .collect();
+    }
+}
+
+impl Completer for ShellHelper {
+    type Candidate = String;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<String>)> {
+        let line = &line[..pos];
+        let start = line.rfind(' ').map_or(0, |index| index + 1);
+        let word = &line[start..];
+        let command = line[..start].split_whitespace().next();
+
+        let candidates: Vec<&String> = match command {
+            None => self.commands.iter().map(|(name, _)| name).collect(),
+            Some(command) if word.starts_with('-') => self
+                .commands
+                .iter()
+                .find(|(name, _)| name == command)
+                .map(|(_, options)| options.iter().collect())
+                .unwrap_or_default(),
+            Some("get" | "remove") => self.ids.iter().collect(),
+            Some(_) => Vec::new(),
+        };
+
+        let candidates = candidates
+            .into_iter()
+            .filter(|candidate| candidate.starts_with(word))
+            .cloned()
+            .collect();
+
+        Ok((start, candidates))
+    }
+}
+
+impl Hinter for ShellHelper {
+    type Hint = String;
+}
+
+impl Highlighter for ShellHelper {}
+
+impl Validator for ShellHelper {}
+
+impl Helper for ShellHelper {}
+
+/// Runs the interactive shell on the data file at `data_file`, taking options
+/// left out of commands from `config`.
+///
+/// The [`Data`] is loaded once and every command runs against it. If
+/// `autosave` is `true`, the data file is saved after every command that
+/// changes the [`Data`]; otherwise changes are kept until `commit` is run.
+pub fn run(data_file: &Path, config: &Config, autosave: bool) -> Result<(), Error> {
+    let mut data = read_data(data_file).map_err(Error::from_data)?;
+    let mut dirty = false;
+    // Whether the registry was reset since it was last saved, so that the
+    // data file must be backed up first.
+    let mut reset = false;
+
+    let mut editor: Editor<ShellHelper, DefaultHistory> = Editor::new()
+        .map_err(|err| Error::Interface(format!("Couldn't start the shell: {err}")))?;
+    let mut helper = ShellHelper::new();
+    helper.update_ids(&data);
+    editor.set_helper(Some(helper));
+
+    let history = dirs::data_dir().map(|mut path| {
+        path.push("users_registry");
+        path.push("shell_history");
+        path
+    });
+
+    if let Some(history) = &history {
+        // The history file doesn't exist on the first run.
+        let _ = editor.load_history(history);
+    }
+
+    println!("Type 'help' to list the commands and 'exit' to leave.");
+
+    loop {
+        let line = match editor.readline(PROMPT) {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted) => continue,
+            Err(ReadlineError::Eof) => {
+                if dirty {
+                    eprintln!("Discarding unsaved changes.");
+                }
+                break;
+            }
+            Err(err) => return Err(Error::Io(format!("Couldn't read the command: {err}"))),
+        };
+
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let _ = editor.add_history_entry(line.as_str());
+
+        let Some(words) = shlex::split(&line) else {
+            eprintln!("The command has unbalanced quotes.");
+            continue;
+        };
+
+        let command = match ShellLine::try_parse_from(words) {
+            Ok(line) => line.command,
+            Err(err) => {
+                let _ = err.print();
+                continue;
+            }
+        };
+
+        let save = match command {
+            ShellCommand::Registry(command) => {
+                let resets = matches!(command, Command::Reset { .. });
+
+                match execute(command, &mut data, config, &mut stdout()) {
+                    Ok(changed) => {
+                        dirty |= changed;
+                        reset |= changed && resets;
+                        changed && autosave
+                    }
+                    Err(err) => {
+                        eprintln!("{err}");
+                        false
+                    }
+                }
+            }
+            ShellCommand::Commit if !dirty => {
+                println!("There are no changes to commit.");
+                false
+            }
+            ShellCommand::Commit => true,
+            ShellCommand::Rollback => {
+                match read_data(data_file) {
+                    Ok(saved) => {
+                        data = saved;
+                        dirty = false;
+                        reset = false;
+                    }
+                    Err(err) => eprintln!("Couldn't read users: {err}"),
+                }
+                false
+            }
+            ShellCommand::Exit { discard } => {
+                if dirty && !discard {
+                    eprintln!("There are unsaved changes. Run 'commit' or 'exit --discard'.");
+                    continue;
+                }
+                break;
+            }
+        };
+
+        if save {
+            if reset && let Err(err) = back_up_reset(data_file, &mut stdout()) {
+                eprintln!("{err}");
+            } else {
+                match save_data(data_file, &data) {
+                    Ok(()) => {
+                        dirty = false;
+                        reset = false;
+                    }
+                    Err(err) => eprintln!("Couldn't save users: {err}"),
+                }
+            }
+        }
+
+        if let Some(helper) = editor.helper_mut() {
+            helper.update_ids(&data);
+        }
+    }
+
+    if let Some(history) = &history {
+        let _ = editor.save_history(history);
+    }
+
+    Ok(())
+}
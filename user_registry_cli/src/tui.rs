@@ -0,0 +1,603 @@
+use std::{io, path::Path};
+
+use ratatui::{
+    DefaultTerminal, Frame,
+    crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    layout::{Constraint, Flex, Layout, Rect},
+    style::{Modifier, Style, Stylize},
+    text::{Line, Span},
+    widgets::{Block, Clear, List, ListItem, ListState, Paragraph},
+};
+use user_registry_lib::{
+    Data, Field, User,
+    command::{read_data, save_data},
+};
+
+use crate::error::Error;
+
+const KEYS_HELP: &str =
+    "↑/↓ move · a add · e edit · d remove · / search · Esc clear search · q quit";
+
+/// A form to add a new [`User`] or edit an existing one.
+struct Form {
+    /// The ID of the edited [`User`], or [`None`] when adding one.
+    id: Option<usize>,
+    values: [String; 4],
+    focus: usize,
+    error: Option<String>,
+}
+
+impl Form {
+    fn new(id: Option<usize>, user: Option<&User>) -> Self {
+        Self {
+            id,
+            values: Field::ALL
+                .map(|field| user.map_or_else(String::new, |user| field.value(user).to_string())),
+            focus: 0,
+            error: None,
+        }
+    }
+
+    fn user(&self) -> User {
+        let [first_name, last_name, email, phone_number] = self.values.clone();
+
+        User {
+            first_name,
+            last_name,
+            email,
+            phone_number,
+        }
+    }
+}
+
+/// What the keyboard currently controls.
+enum Mode {
+    List,
+    Search,
+    Form(Form),
+    ConfirmRemove(usize),
+}
+
+/// The state of the terminal UI.
+struct Tui<'a> {
+    data_file: &'a Path,
+    data: Data,
+    /// The [`Data`] as last saved, restored when saving a change fails.
+    saved: Data,
+    query: String,
+    list: ListState,
+    mode: Mode,
+    status: String,
+}
+
+impl<'a> Tui<'a> {
+    fn new(data_file: &'a Path, data: Data) -> Self {
+        let mut tui = Self {
+            data_file,
+            saved: data.clone(),
+            data,
+            query: String::new(),
+            list: ListState::default(),
+            mode: Mode::List,
+            status: String::from(KEYS_HELP),
+        };
+        tui.clamp_selection();
+        tui
+    }
+
+    /// Returns the IDs of the [`User`]s matching the search query.
+    fn visible(&self) -> Vec<usize> {
+        self.data
+            .users()
+            .into_iter()
+            .filter(|(_, user)| user.matches(&self.query))
+            .map(|(id, _)| id)
+            .collect()
+    }
+
+    fn selected_id(&self) -> Option<usize> {
+        self.list
+            .selected()
+            .and_then(|index| self.visible().get(index).copied())
+    }
+
+    fn clamp_selection(&mut self) {
+        let len = self.visible().len();
+
+        match self.list.selected() {
+            _ if len == 0 => self.list.select(None),
+            Some(index) if index >= len => self.list.select(Some(len - 1)),
+            None => self.list.select(Some(0)),
+            Some(_) => {}
+        }
+    }
+
+    fn select_id(&mut self, id: usize) {
+        let index = self.visible().iter().position(|visible| *visible == id);
+        self.list.select(index);
+        self.clamp_selection();
+    }
+
+    /// Saves the [`Data`], undoing the change if saving fails so that the
+    /// list never shows unsaved users.
+    fn save(&mut self, message: String) {
+        match save_data(self.data_file, &self.data) {
+            Ok(()) => {
+                self.saved = self.data.clone();
+                self.status = message;
+            }
+            Err(err) => {
+                self.data = self.saved.clone();
+                self.status = format!("Couldn't save users, so the change was undone: {err}");
+            }
+        }
+    }
+
+    /// Handles a key press, returning `false` when the UI should close.
+    fn handle_key(&mut self, key: KeyEvent) -> bool {
+        match std::mem::replace(&mut self.mode, Mode::List) {
+            Mode::List => return self.handle_list_key(key),
+            Mode::Search => self.handle_search_key(key),
+            Mode::Form(form) => self.handle_form_key(form, key),
+            Mode::ConfirmRemove(id) => self.handle_confirm_key(id, key),
+        }
+
+        true
+    }
+
+    fn handle_list_key(&mut self, key: KeyEvent) -> bool {
+        match key.code {
+            KeyCode::Char('q') => return false,
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return false,
+            KeyCode::Down | KeyCode::Char('j') => self.list.select_next(),
+            KeyCode::Up | KeyCode::Char('k') => self.list.select_previous(),
+            KeyCode::Home => self.list.select_first(),
+            KeyCode::End => self.list.select_last(),
+            KeyCode::Char('/') => self.mode = Mode::Search,
+            KeyCode::Esc => {
+                self.query.clear();
+                self.clamp_selection();
+            }
+            KeyCode::Char('a') => self.mode = Mode::Form(Form::new(None, None)),
+            KeyCode::Char('e') | KeyCode::Enter => {
+                if let Some(id) = self.selected_id() {
+                    self.mode = Mode::Form(Form::new(Some(id), self.data.user(id)));
+                }
+            }
+            KeyCode::Char('d') | KeyCode::Delete => {
+                if let Some(id) = self.selected_id() {
+                    self.mode = Mode::ConfirmRemove(id);
+                }
+            }
+            _ => {}
+        }
+
+        self.clamp_selection();
+        true
+    }
+
+    fn handle_search_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Enter => {}
+            KeyCode::Esc => self.query.clear(),
+            KeyCode::Backspace => {
+                self.query.pop();
+                self.mode = Mode::Search;
+            }
+            KeyCode::Char(c) => {
+                self.query.push(c);
+                self.mode = Mode::Search;
+            }
+            _ => self.mode = Mode::Search,
+        }
+
+        self.list.select(Some(0));
+        self.clamp_selection();
+    }
+
+    fn handle_form_key(&mut self, mut form: Form, key: KeyEvent) {
+        match key.code {
+            KeyCode::Esc => {
+                self.status = String::from(KEYS_HELP);
+                return;
+            }
+            KeyCode::Tab | KeyCode::Down => form.focus = (form.focus + 1) % form.values.len(),
+            KeyCode::BackTab | KeyCode::Up => {
+                form.focus = (form.focus + form.values.len() - 1) % form.values.len();
+            }
+            KeyCode::Backspace => {
+                form.values[form.focus].pop();
+            }
+            KeyCode::Char(c) => form.values[form.focus].push(c),
+            KeyCode::Enter => {
+                let user = form.user();
+
+                if let Err(err) = user.validate() {
+                    form.focus = Field::ALL
+                        .iter()
+                        .position(|field| *field == err.field)
+                        .unwrap_or(0);
+                    form.error = Some(err.to_string());
+                } else {
+                    let id = match form.id {
+                        Some(id) => {
+                            self.data.insert_user(id, user);
+                            self.save(format!("Saved user {id}."));
+                            id
+                        }
+                        None => {
+                            let id = self.data.add_user(user);
+                            self.save(format!("Added user {id}."));
+                            id
+                        }
+                    };
+
+                    self.select_id(id);
+                    return;
+                }
+            }
+            _ => {}
+        }
+
+        self.mode = Mode::Form(form);
+    }
+
+    fn handle_confirm_key(&mut self, id: usize, key: KeyEvent) {
+        match key.code {
+            KeyCode::Char('y') => {
+                if self.data.remove_user(id).is_some() {
+                    self.save(format!("Removed user {id}."));
+                }
+                self.clamp_selection();
+            }
+            KeyCode::Char('n') | KeyCode::Esc => {}
+            _ => self.mode = Mode::ConfirmRemove(id),
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [main, status] =
+            Layout::vertical([Constraint::Fill(1), Constraint::Length(1)]).areas(frame.area());
+        let [list_area, detail_area] =
+            Layout::horizontal([Constraint::Percentage(40), Constraint::Percentage(60)])
+                .areas(main);
+
+        self.draw_list(frame, list_area);
+        self.draw_detail(frame, detail_area);
+        frame.render_widget(Paragraph::new(self.status.as_str()).dim(), status);
+
+        match &self.mode {
+            Mode::Form(form) => draw_form(frame, form),
+            Mode::ConfirmRemove(id) => {
+                let name = self
+                    .data
+                    .user(*id)
+                    .map(|user| format!("{} {}", user.first_name, user.last_name))
+                    .unwrap_or_default();
+                let area = popup(frame.area(), 50, 3);
+
+                frame.render_widget(Clear, area);
+                frame.render_widget(
+                    Paragraph::new(format!("Remove {name} (user {id})? [y/n]"))
+                        .block(Block::bordered().title(" Confirm ")),
+                    area,
+                );
+            }
+            Mode::List | Mode::Search => {}
+        }
+    }
+
+    fn draw_list(&mut self, frame: &mut Frame, area: Rect) {
+        let visible = self.visible();
+        let total = self.data.users().len();
+
+        let [search_area, list_area] =
+            if self.query.is_empty() && !matches!(self.mode, Mode::Search) {
+                [Rect::default(), area]
+            } else {
+                Layout::vertical([Constraint::Length(3), Constraint::Fill(1)]).areas(area)
+            };
+
+        if !search_area.is_empty() {
+            let style = if matches!(self.mode, Mode::Search) {
+                Style::new().bold()
+            } else {
+                Style::new()
+            };
+
+            frame.render_widget(
+                Paragraph::new(self.query.as_str())
+                    .block(Block::bordered().title(" Search ").style(style)),
+                search_area,
+            );
+        }
+
+        let items: Vec<ListItem> = visible
+            .iter()
+            .filter_map(|id| self.data.user(*id).map(|user| (id, user)))
+            .map(|(id, user)| {
+                ListItem::new(format!("{id:>4}  {} {}", user.first_name, user.last_name))
+            })
+            .collect();
+
+        let list = List::new(items)
+            .block(Block::bordered().title(format!(" Users ({} of {total}) ", visible.len())))
+            .highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+
+        frame.render_stateful_widget(list, list_area, &mut self.list);
+    }
+
+    fn draw_detail(&self, frame: &mut Frame, area: Rect) {
+        let block = Block::bordered().title(" Details ");
+
+        let Some((id, user)) = self
+            .selected_id()
+            .and_then(|id| self.data.user(id).map(|user| (id, user)))
+        else {
+            frame.render_widget(Paragraph::new("No user selected.").block(block), area);
+            return;
+        };
+
+        let mut lines = vec![Line::from(vec![
+            Span::from("ID: ").bold(),
+            Span::from(id.to_string()),
+        ])];
+
+        for field in Field::ALL {
+            lines.push(Line::from(vec![
+                Span::from(format!("{field}: ")).bold(),
+                Span::from(field.value(user)),
+            ]));
+        }
+
+        frame.render_widget(Paragraph::new(lines).block(block), area);
+    }
+}
+
+/// Returns a rectangle of the given width percentage and height, centered in
+/// `area`.
+fn popup(area: Rect, percent_x: u16, height: u16) -> Rect {
+    let [area] = Layout::vertical([Constraint::Length(height)])
+        .flex(Flex::Center)
+        .areas(area);
+    let [area] = Layout::horizontal([Constraint::Percentage(percent_x)])
+        .flex(Flex::Center)
+        .areas(area);
+    area
+}
+
+fn draw_form(frame: &mut Frame, form: &Form) {
+    let title = match form.id {
+        Some(id) => format!(" Edit user {id} "),
+        None => String::from(" Add user "),
+    };
+
+    let mut lines: Vec<Line> = Field::ALL
+        .into_iter()
+        .zip(&form.values)
+        .enumerate()
+        .map(|(index, (field, value))| {
+            let label = Span::from(format!("{field:>13}: ")).bold();
+
+            if index == form.focus {
+                Line::from(vec![label, Span::from(format!("{value}_")).reversed()])
+            } else {
+                Line::from(vec![label, Span::from(value.as_str())])
+            }
+        })
+        .collect();
+
+    lines.push(Line::default());
+    lines.push(match &form.error {
+        Some(error) => Line::from(error.as_str()).red(),
+        None => Line::from("Tab next field · Enter save · Esc cancel").dim(),
+    });
+
+    let area = popup(frame.area(), 60, lines.len() as u16 + 2);
+    frame.render_widget(Clear, area);
+    frame.render_widget(
+        Paragraph::new(lines).block(Block::bordered().title(title)),
+        area,
+    );
+}
+
+fn event_loop(terminal: &mut DefaultTerminal, tui: &mut Tui) -> Result<(), io::Error> {
+    loop {
+        terminal.draw(|frame| tui.draw(frame))?;
+
+        if let Event::Key(key) = event::read()?
+            && key.kind == KeyEventKind::Press
+            && !tui.handle_key(key)
+        {
+            return Ok(());
+        }
+    }
+}
+
+/// Runs the terminal UI on the data file at `data_file`.
+///
+/// Every change is saved to the data file as soon as it is confirmed.
+pub fn run(data_file: &Path) -> Result<(), Error> {
+    let data = read_data(data_file).map_err(Error::from_data)?;
+    let mut tui = Tui::new(data_file, data);
+
+    let mut terminal = ratatui::try_init()
+        .map_err(|err| Error::Interface(format!("Couldn't start the terminal UI: {err}")))?;
+    let result = event_loop(&mut terminal, &mut tui);
+    ratatui::restore();
+
+    result.map_err(|err| Error::Interface(format!("An error occurred in the terminal UI: {err}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, path::PathBuf};
+
+    use super::*;
+
+    /// Helper function to create a [`User`] with data for testing purposes.
+    fn first_user() -> User {
+        User {
+            first_name: String::from("Ann"),
+            last_name: String::from("Lee"),
+            email: String::from("ann@example.com"),
+            phone_number: String::from("555"),
+        }
+    }
+
+    /// Helper function to create a [`User`] with data for testing purposes.
+    fn second_user() -> User {
+        User {
+            first_name: String::from("Bob"),
+            last_name: String::from("Ray"),
+            email: String::from("bob@example.com"),
+            phone_number: String::from("556"),
+        }
+    }
+
+    /// Helper function to create a [`Data`] with two [`User`]s.
+    fn data() -> Data {
+        let mut data = Data::new();
+        data.add_user(first_user());
+        data.add_user(second_user());
+        data
+    }
+
+    /// Helper function to return the path of a data file for a test, in a
+    /// temporary folder.
+    fn data_file(name: &str) -> PathBuf {
+        let folder = env::temp_dir().join("users_registry_tui_test");
+        fs::create_dir_all(&folder).unwrap();
+        folder.join(name)
+    }
+
+    /// Helper function to press the keys with the given `codes`.
+    fn press(tui: &mut Tui, codes: impl IntoIterator<Item = KeyCode>) {
+        for code in codes {
+            tui.handle_key(KeyEvent::new(code, KeyModifiers::NONE));
+        }
+    }
+
+    /// Helper function to type `text` one character at a time.
+    fn type_text(tui: &mut Tui, text: &str) {
+        press(tui, text.chars().map(KeyCode::Char));
+    }
+
+    /// Tests moving through the list and searching it.
+    ///
+    /// This test ensures that:
+    /// - The arrow keys move the selection and stay within the list.
+    /// - Typing a search query shows only the matching users.
+    /// - Escape clears the query and shows every user again.
+    /// - `q` closes the UI.
+    #[test]
+    fn navigate_and_search() {
+        let path = data_file("navigate.json");
+        let mut tui = Tui::new(&path, data());
+
+        assert_eq!(tui.selected_id(), Some(0));
+        press(&mut tui, [KeyCode::Down, KeyCode::Down]);
+        assert_eq!(tui.selected_id(), Some(1));
+        press(&mut tui, [KeyCode::Up]);
+        assert_eq!(tui.selected_id(), Some(0));
+
+        press(&mut tui, [KeyCode::Char('/')]);
+        type_text(&mut tui, "BOB");
+        assert_eq!(tui.visible(), vec![1]);
+        assert_eq!(tui.selected_id(), Some(1));
+
+        press(&mut tui, [KeyCode::Enter]);
+        assert!(matches!(tui.mode, Mode::List));
+        assert_eq!(tui.query, "BOB");
+
+        press(&mut tui, [KeyCode::Esc]);
+        assert_eq!(tui.visible(), vec![0, 1]);
+
+        assert!(!tui.handle_key(KeyEvent::new(KeyCode::Char('q'), KeyModifiers::NONE)));
+    }
+
+    /// Tests adding and editing [`User`]s with the form.
+    ///
+    /// This test ensures that:
+    /// - An invalid field keeps the form open, focused on that field.
+    /// - A valid user is added, selected and saved to the data file.
+    /// - Editing a user keeps their ID.
+    #[test]
+    fn add_and_edit_users() {
+        let path = data_file("form.json");
+        let mut tui = Tui::new(&path, data());
+
+        press(&mut tui, [KeyCode::Char('a')]);
+        for value in ["Cat", "Day", "cat", "557"] {
+            type_text(&mut tui, value);
+            press(&mut tui, [KeyCode::Tab]);
+        }
+        press(&mut tui, [KeyCode::Enter]);
+
+        let Mode::Form(form) = &tui.mode else {
+            panic!("The form should stay open");
+        };
+        assert_eq!(form.focus, 2);
+        assert!(form.error.is_some());
+
+        type_text(&mut tui, "@example.com");
+        press(&mut tui, [KeyCode::Enter]);
+
+        assert!(matches!(tui.mode, Mode::List));
+        assert_eq!(tui.status, "Added user 2.");
+        assert_eq!(tui.selected_id(), Some(2));
+        assert_eq!(read_data(&path).unwrap().users().len(), 3);
+
+        press(
+            &mut tui,
+            [KeyCode::Char('e'), KeyCode::Tab, KeyCode::Backspace],
+        );
+        type_text(&mut tui, "i");
+        press(&mut tui, [KeyCode::Enter]);
+        let saved = read_data(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(tui.status, "Saved user 2.");
+        assert_eq!(saved.user(2).unwrap().last_name, "Dai");
+    }
+
+    /// Tests removing a [`User`] after confirmation.
+    ///
+    /// This test ensures that:
+    /// - Declining the confirmation keeps the user.
+    /// - Accepting it removes the user and saves the data file.
+    #[test]
+    fn remove_after_confirmation() {
+        let path = data_file("remove.json");
+        let mut tui = Tui::new(&path, data());
+
+        press(&mut tui, [KeyCode::Char('d')]);
+        assert!(matches!(tui.mode, Mode::ConfirmRemove(0)));
+        press(&mut tui, [KeyCode::Char('n')]);
+        assert_eq!(tui.visible(), vec![0, 1]);
+
+        press(&mut tui, [KeyCode::Char('d'), KeyCode::Char('y')]);
+        let saved = read_data(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(tui.visible(), vec![1]);
+        assert_eq!(tui.selected_id(), Some(1));
+        assert_eq!(saved.users(), vec![(1, &second_user())]);
+    }
+
+    /// Tests undoing a change that can't be saved.
+    ///
+    /// This test ensures that:
+    /// - The [`Data`] goes back to its last saved state.
+    /// - The status reports that the change was undone.
+    #[test]
+    fn undo_failed_save() {
+        let folder = data_file("");
+        let mut tui = Tui::new(&folder, data());
+
+        press(&mut tui, [KeyCode::Char('d'), KeyCode::Char('y')]);
+
+        assert_eq!(tui.visible(), vec![0, 1]);
+        assert!(tui.status.starts_with("Couldn't save users"));
+    }
+}
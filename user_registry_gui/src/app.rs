@@ -1,6 +1,6 @@
-use iced::Element;
+use iced::{Element, Subscription, Task};
 
-use crate::{message::Message, page::Page, pages, state::State};
+use crate::{message::Message, page::Page, pages, state::State, storage, watch};
 
 pub struct App;
 
@@ -8,18 +8,45 @@ impl App {
     pub fn title(state: &State) -> String {
         match state.page {
             Page::Main => pages::main::title(),
+            Page::Add => pages::add::title(),
+            Page::Details(id) => pages::details::title(state, id),
+            Page::Edit(id) => pages::edit::title(state, id),
         }
     }
 
-    pub fn update(state: &mut State, _: Message) {
+    pub fn update(state: &mut State, message: Message) -> Task<Message> {
+        match message {
+            Message::Loaded(_)
+            | Message::ProfileLoaded(_)
+            | Message::Saved(_)
+            | Message::PreferencesSaved(_) => return storage::update(state, message),
+            Message::DataFileChanged
+            | Message::Reloaded(_)
+            | Message::ReloadData
+            | Message::KeepChanges => return watch::update(state, message),
+            _ => {}
+        }
+
         match state.page {
-            Page::Main => pages::main::update(),
+            Page::Main => pages::main::update(state, message),
+            Page::Add => pages::add::update(state, message),
+            Page::Details(_) => pages::details::update(state, message),
+            Page::Edit(id) => pages::edit::update(state, id, message),
         }
     }
 
-    pub fn view(state: &State) -> Element<Message> {
-        match state.page {
+    pub fn view(state: &State) -> Element<'_, Message> {
+        let page = match state.page {
             Page::Main => pages::main::view(state),
-        }
+            Page::Add => pages::add::view(state),
+            Page::Details(id) => pages::details::view(state, id),
+            Page::Edit(id) => pages::edit::view(state, id),
+        };
+
+        watch::view(state, page)
+    }
+
+    pub fn subscription(state: &State) -> Subscription<Message> {
+        watch::subscription(state.data_file.clone())
     }
 }
@@ -1,4 +1,6 @@
-use iced::{Font, font::Weight};
+use std::time::Duration;
+
+use iced::{Color, Font, font::Weight};
 
 pub const WINDOW_PADDING: u16 = 15;
 
@@ -22,6 +24,17 @@ pub const BUTTON_PADDING: [u16; 2] = [BUTTON_PADDING_VERTICAL, BUTTON_PADDING_HO
 pub const BUTTON_WIDTH: u16 = 80;
 pub const BUTTON_HEIGHT: u16 = 44;
 
+pub const DIALOG_WIDTH: u16 = 400;
+
+pub const DEFAULT_COLUMN_WIDTHS: [f32; 5] = [60.0, 140.0, 140.0, 240.0, 160.0];
+pub const MIN_COLUMN_WIDTH: f32 = 40.0;
+pub const RESIZE_HANDLE_WIDTH: f32 = 8.0;
+
+/// How often the data file is checked for changes.
+pub const WATCH_INTERVAL: Duration = Duration::from_secs(1);
+
+pub const HIGHLIGHT_COLOR: Color = Color::from_rgb(1.0, 0.84, 0.0);
+
 pub const BOLD_FONT: Font = {
     let mut font = Font::DEFAULT;
     font.weight = Weight::Bold;
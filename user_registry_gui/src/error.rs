@@ -4,10 +4,14 @@ use std::{
     io,
 };
 
+use user_registry_lib::{ConfigError, DataFileError};
+
 #[derive(Debug)]
 pub enum Error {
     IoError(io::Error),
     IcedError(iced::Error),
+    ConfigError(ConfigError),
+    DataFileError(DataFileError),
 }
 
 impl From<io::Error> for Error {
@@ -22,11 +26,25 @@ impl From<iced::Error> for Error {
     }
 }
 
+impl From<ConfigError> for Error {
+    fn from(err: ConfigError) -> Self {
+        Self::ConfigError(err)
+    }
+}
+
+impl From<DataFileError> for Error {
+    fn from(err: DataFileError) -> Self {
+        Self::DataFileError(err)
+    }
+}
+
 impl Display for Error {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         match self {
             Self::IoError(err) => write!(f, "IO Error: {err}"),
             Self::IcedError(err) => write!(f, "Iced Error: {err}"),
+            Self::ConfigError(err) => write!(f, "Config Error: {err}"),
+            Self::DataFileError(err) => write!(f, "Data File Error: {err}"),
         }
     }
 }
@@ -0,0 +1,142 @@
+use iced::{
+    Element,
+    Length::Fill,
+    widget::{column, container, horizontal_space, row, scrollable, text, text_input},
+};
+use user_registry_lib::{Field, User, ValidationError};
+
+use crate::{
+    constants::{
+        BIG_TEXT_SIZE, BUTTON_HEIGHT, BUTTON_PADDING, LARGE_TEXT_SIZE, NORMAL_SPACING,
+        NORMAL_TEXT_SIZE, SMALL_PADDING, WINDOW_PADDING,
+    },
+    message::Message,
+    utils::{create_danger_button, create_primary_button},
+};
+
+fn empty_user() -> User {
+    User {
+        first_name: String::new(),
+        last_name: String::new(),
+        email: String::new(),
+        phone_number: String::new(),
+    }
+}
+
+/// The values entered in a form for every [`User`] field.
+pub struct UserForm {
+    pub user: User,
+
+    /// The values the form started with, to tell whether it was changed.
+    pub original: User,
+
+    /// Whether saving was attempted, so that errors of fields that were left
+    /// empty are shown too.
+    pub submitted: bool,
+
+    /// Whether leaving the form with unsaved changes waits for confirmation.
+    pub discarding: bool,
+}
+
+impl Default for UserForm {
+    fn default() -> Self {
+        Self::with_user(empty_user())
+    }
+}
+
+impl UserForm {
+    /// Creates a form filled with the fields of `user`.
+    pub fn with_user(user: User) -> Self {
+        Self {
+            original: user.clone(),
+            user,
+            submitted: false,
+            discarding: false,
+        }
+    }
+
+    /// Returns whether a field was changed since the form was created.
+    pub fn is_dirty(&self) -> bool {
+        self.user != self.original
+    }
+
+    /// Sets the value of a field.
+    pub fn input(&mut self, field: Field, value: String) {
+        *field.value_mut(&mut self.user) = value;
+    }
+
+    /// Returns the error of a field to show, if any.
+    pub fn error(&self, field: Field) -> Option<ValidationError> {
+        let value = field.value(&self.user);
+
+        if !self.submitted && value.is_empty() {
+            return None;
+        }
+
+        field.validate(value).err()
+    }
+
+    /// Returns the entered [`User`] if every field is valid, and shows the
+    /// errors otherwise.
+    pub fn submit(&mut self) -> Option<User> {
+        self.submitted = true;
+        self.user.validate().ok().map(|()| self.user.clone())
+    }
+
+    pub fn view(&self) -> Element<'_, Message> {
+        let mut fields = column![].spacing(NORMAL_SPACING);
+
+        for field in Field::ALL {
+            let mut input = column![
+                text(field.to_string()).size(NORMAL_TEXT_SIZE),
+                text_input(&field.to_string(), field.value(&self.user))
+                    .on_input(move |value| Message::FormInput(field, value))
+                    .on_submit(Message::SaveForm)
+                    .padding(SMALL_PADDING)
+                    .width(Fill),
+            ]
+            .spacing(SMALL_PADDING);
+
+            if let Some(error) = self.error(field) {
+                input = input.push(
+                    text(error.to_string())
+                        .size(NORMAL_TEXT_SIZE)
+                        .style(text::danger),
+                );
+            }
+
+            fields = fields.push(input);
+        }
+
+        fields.into()
+    }
+
+    /// Shows the form as a page with a `title`, Cancel and Save buttons, and
+    /// the `error` of the last save if any.
+    pub fn view_page<'a>(&'a self, title: String, error: Option<&'a str>) -> Element<'a, Message> {
+        let header = row![
+            text(title).size(LARGE_TEXT_SIZE).width(Fill),
+            container(row![
+                create_danger_button(text("Cancel").size(BIG_TEXT_SIZE).center())
+                    .padding(BUTTON_PADDING)
+                    .on_press(Message::CancelForm),
+                horizontal_space().width(NORMAL_SPACING),
+                create_primary_button(text("Save").size(BIG_TEXT_SIZE).center())
+                    .padding(BUTTON_PADDING)
+                    .on_press(Message::SaveForm),
+            ])
+            .center_y(BUTTON_HEIGHT),
+        ]
+        .spacing(NORMAL_SPACING);
+
+        let mut page = column![header].spacing(NORMAL_SPACING);
+
+        if let Some(error) = error {
+            page = page.push(text(error).size(NORMAL_TEXT_SIZE).style(text::danger));
+        }
+
+        page.push(scrollable(self.view()).spacing(NORMAL_SPACING))
+            .padding(WINDOW_PADDING)
+            .into()
+    }
+}
@@ -1,24 +1,36 @@
 mod app;
 mod constants;
 mod error;
+mod form;
 mod message;
 mod page;
 mod pages;
+mod preferences;
 mod state;
+mod storage;
+mod table;
 mod utils;
+mod watch;
 
 use app::App;
 pub use error::Error;
 use state::State;
 
-use iced::Task;
-
 use std::path::Path;
 
-pub fn run<P: AsRef<Path>>(data_file: P) -> Result<(), Error> {
-    let state = State::with_data_file(data_file)?;
+/// Runs the GUI on the data file at `data_file`, selected through the profile
+/// called `profile` if any. With `read_only`, the users can't be changed.
+pub fn run<P: AsRef<Path>>(
+    data_file: P,
+    profile: Option<String>,
+    read_only: bool,
+) -> Result<(), Error> {
+    let state = State::with_data_file(&data_file, profile, read_only)?;
+    let load = storage::load(data_file.as_ref().to_path_buf());
 
-    iced::application(App::title, App::update, App::view).run_with(|| (state, Task::none()))?;
+    iced::application(App::title, App::update, App::view)
+        .subscription(App::subscription)
+        .run_with(|| (state, load))?;
 
     Ok(())
 }
@@ -1,35 +1,51 @@
-use std::{fs, process};
+use std::{path::PathBuf, process};
 
+use clap::Parser;
 use user_registry_gui::run;
+use user_registry_lib::Config;
+
+/// Graphical interface to register users in a file with their data.
+#[derive(Parser, Debug)]
+#[command(version, about)]
+struct Args {
+    /// File to load and save user data (defaults to the configured file, or
+    /// the data directory).
+    #[arg(short, long, value_name = "FILE")]
+    data: Option<PathBuf>,
+
+    /// Profile of the configuration file to use.
+    #[arg(long, value_name = "NAME")]
+    profile: Option<String>,
+
+    /// Show the users without allowing any change to them.
+    #[arg(long)]
+    read_only: bool,
+}
 
 pub fn main() {
-    let data_file = dirs::data_dir()
-        .map(|mut path| {
-            path.push("users_registry");
-            path.push("users.json");
-            path
-        })
-        .unwrap_or_else(|| {
-            eprintln!("Couldn't get data file path. Try using --data to specify one.");
-            process::exit(1);
-        });
-
-    let parent = data_file.parent().unwrap_or_else(|| {
-        eprintln!("Couldn't get data file parent.");
+    let args = Args::parse();
+
+    let mut config = Config::load(args.profile.as_deref()).unwrap_or_else(|err| {
+        eprintln!("Couldn't load configuration: {err}");
         process::exit(1);
     });
 
-    if let Err(err) = fs::create_dir_all(parent) {
-        eprintln!("Failed to create data directory: {err}");
-        process::exit(1);
+    // The data file given on the command line belongs to no profile.
+    if args.data.is_some() {
+        config.profile = None;
     }
 
-    if data_file.exists() && !data_file.is_file() {
-        eprintln!("The data path must be a file. Specify another one.");
-        process::exit(1);
+    let data_file = if args.read_only {
+        config.find_data_file(args.data)
+    } else {
+        config.prepare_data_file(args.data)
     }
+    .unwrap_or_else(|err| {
+        eprintln!("{err}");
+        process::exit(1);
+    });
 
-    if let Err(err) = run(&data_file) {
+    if let Err(err) = run(&data_file, config.profile, args.read_only) {
         eprintln!("An error occurred in the GUI: {err}");
         process::exit(1);
     }
@@ -1,4 +1,39 @@
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+use std::path::PathBuf;
+
+use iced::{Point, widget::scrollable::AbsoluteOffset};
+use user_registry_lib::{Data, Field};
+
+use crate::preferences::{Column, Layout};
+
+#[derive(Debug, Clone)]
 pub enum Message {
-    Null,
+    SelectProfile(String),
+    Search(String),
+    ScrollUsers(AbsoluteOffset),
+    SetLayout(Layout),
+    SortBy(Column),
+    StartResize(usize),
+    ResizeColumn(Point),
+    EndResize,
+    ShowList,
+    ShowUser(usize),
+    RemoveUser(usize),
+    ConfirmRemove,
+    CancelRemove,
+    CopyValue(String),
+    ShowAddForm,
+    FormInput(Field, String),
+    SaveForm,
+    CancelForm,
+    EditUser(usize),
+    DiscardChanges,
+    KeepEditing,
+    Loaded(Result<Data, String>),
+    ProfileLoaded(Result<(String, PathBuf, Data), String>),
+    Saved(Result<Data, String>),
+    PreferencesSaved(Result<(), String>),
+    DataFileChanged,
+    Reloaded(Result<Data, String>),
+    ReloadData,
+    KeepChanges,
 }
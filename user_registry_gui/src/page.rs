@@ -2,4 +2,7 @@
 pub enum Page {
     #[default]
     Main,
+    Add,
+    Details(usize),
+    Edit(usize),
 }
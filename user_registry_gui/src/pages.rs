@@ -1 +1,4 @@
+pub mod add;
+pub mod details;
+pub mod edit;
 pub mod main;
@@ -0,0 +1,36 @@
+use iced::{Element, Task};
+
+use crate::{form::UserForm, message::Message, pages, state::State, storage};
+
+pub fn title() -> String {
+    String::from("Add user")
+}
+
+pub fn update(state: &mut State, message: Message) -> Task<Message> {
+    match message {
+        Message::FormInput(field, value) => {
+            state.form.input(field, value);
+            Task::none()
+        }
+        Message::SaveForm => {
+            let Some(user) = state.form.submit() else {
+                return Task::none();
+            };
+
+            state.data.add_user(user);
+            state.form = UserForm::default();
+
+            let save = storage::save(state);
+            Task::batch([save, pages::main::open(state)])
+        }
+        Message::CancelForm => {
+            state.form = UserForm::default();
+            pages::main::open(state)
+        }
+        _ => Task::none(),
+    }
+}
+
+pub fn view(state: &State) -> Element<'_, Message> {
+    state.form.view_page(title(), state.error.as_deref())
+}
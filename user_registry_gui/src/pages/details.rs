@@ -0,0 +1,117 @@
+use iced::{
+    Element,
+    Length::Fill,
+    Task,
+    border::rounded,
+    clipboard,
+    widget::{column, container, horizontal_space, row, scrollable, text},
+};
+use user_registry_lib::{Field, User};
+
+use crate::{
+    constants::{
+        BIG_TEXT_SIZE, BOLD_FONT, BUTTON_HEIGHT, BUTTON_PADDING, LARGE_TEXT_SIZE, MEDIUM_TEXT_SIZE,
+        NORMAL_PADDING, NORMAL_SPACING, NORMAL_TEXT_SIZE, OUTER_BORDER_RADIUS, WINDOW_PADDING,
+    },
+    message::Message,
+    pages,
+    state::State,
+    utils::create_primary_button,
+};
+
+pub fn title(state: &State, id: usize) -> String {
+    match state.data.user(id) {
+        Some(user) => format!("{} {}", user.first_name, user.last_name),
+        None => format!("User {id}"),
+    }
+}
+
+pub fn update(state: &mut State, message: Message) -> Task<Message> {
+    match message {
+        Message::CopyValue(value) => clipboard::write(value),
+        Message::ShowList => pages::main::open(state),
+        Message::EditUser(id) => {
+            pages::edit::open(state, id);
+            Task::none()
+        }
+        _ => Task::none(),
+    }
+}
+
+pub fn view(state: &State, id: usize) -> Element<'_, Message> {
+    let header = row![
+        text(title(state, id)).size(LARGE_TEXT_SIZE).width(Fill),
+        container(row![
+            create_primary_button(text("Back").size(BIG_TEXT_SIZE).center())
+                .padding(BUTTON_PADDING)
+                .on_press(Message::ShowList),
+            horizontal_space().width(NORMAL_SPACING),
+            create_primary_button(text("Edit").size(BIG_TEXT_SIZE).center())
+                .padding(BUTTON_PADDING)
+                .on_press_maybe(
+                    state
+                        .data
+                        .user(id)
+                        .filter(|_| state.is_editable())
+                        .map(|_| Message::EditUser(id)),
+                ),
+        ])
+        .center_y(BUTTON_HEIGHT),
+    ]
+    .spacing(NORMAL_SPACING);
+
+    let content: Element<'_, Message> = match state.data.user(id) {
+        Some(user) => scrollable(create_fields(id, user))
+            .spacing(NORMAL_SPACING)
+            .into(),
+        None => text(format!("The user {id} doesn't exist."))
+            .size(NORMAL_TEXT_SIZE)
+            .style(text::danger)
+            .into(),
+    };
+
+    let mut page = column![header].spacing(NORMAL_SPACING);
+
+    if let Some(error) = &state.error {
+        page = page.push(text(error).size(NORMAL_TEXT_SIZE).style(text::danger));
+    }
+
+    page.push(content).padding(WINDOW_PADDING).into()
+}
+
+fn create_fields(id: usize, user: &User) -> Element<'_, Message> {
+    let mut fields =
+        column![create_field(String::from("ID"), id.to_string())].spacing(NORMAL_SPACING);
+
+    for field in Field::ALL {
+        fields = fields.push(create_field(
+            field.to_string(),
+            field.value(user).to_string(),
+        ));
+    }
+
+    fields.into()
+}
+
+fn create_field<'a>(label: String, value: String) -> Element<'a, Message> {
+    container(row![
+        column![
+            text(label).size(NORMAL_TEXT_SIZE).font(BOLD_FONT),
+            text(value.clone()).size(MEDIUM_TEXT_SIZE),
+        ]
+        .width(Fill),
+        container(
+            create_primary_button(text("Copy").size(NORMAL_TEXT_SIZE).center())
+                .on_press(Message::CopyValue(value)),
+        )
+        .center_y(BUTTON_HEIGHT),
+    ])
+    .style(|theme| {
+        container::Style::default()
+            .background(theme.extended_palette().background.weak.color)
+            .border(rounded(OUTER_BORDER_RADIUS))
+    })
+    .padding(NORMAL_PADDING)
+    .width(Fill)
+    .into()
+}
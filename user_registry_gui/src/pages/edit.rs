@@ -0,0 +1,70 @@
+use iced::{Element, Task, widget::stack};
+
+use crate::{
+    form::UserForm, message::Message, page::Page, state::State, storage, utils::create_dialog,
+};
+
+pub fn title(state: &State, id: usize) -> String {
+    match state.data.user(id) {
+        Some(user) => format!("Edit {} {}", user.first_name, user.last_name),
+        None => format!("Edit user {id}"),
+    }
+}
+
+/// Opens the form to edit the user with the given ID, filled with its fields.
+pub fn open(state: &mut State, id: usize) {
+    if let Some(user) = state.data.user(id) {
+        state.form = UserForm::with_user(user.clone());
+        state.error = None;
+        state.page = Page::Edit(id);
+    }
+}
+
+/// Leaves the form for the detail page of the user.
+fn close(state: &mut State, id: usize) {
+    state.form = UserForm::default();
+    state.error = None;
+    state.page = Page::Details(id);
+}
+
+pub fn update(state: &mut State, id: usize, message: Message) -> Task<Message> {
+    match message {
+        Message::FormInput(field, value) => state.form.input(field, value),
+        Message::SaveForm => {
+            let Some(user) = state.form.submit() else {
+                return Task::none();
+            };
+
+            state.data.insert_user(id, user);
+            close(state, id);
+            return storage::save(state);
+        }
+        Message::CancelForm if state.form.is_dirty() => state.form.discarding = true,
+        Message::CancelForm | Message::DiscardChanges => close(state, id),
+        Message::KeepEditing => state.form.discarding = false,
+        _ => {}
+    }
+
+    Task::none()
+}
+
+pub fn view(state: &State, id: usize) -> Element<'_, Message> {
+    let page = state
+        .form
+        .view_page(title(state, id), state.error.as_deref());
+
+    if !state.form.discarding {
+        return page;
+    }
+
+    stack![
+        page,
+        create_dialog(
+            String::from("Discard changes"),
+            String::from("The changes to this user are not saved. Discard them?"),
+            ("Keep editing", Message::KeepEditing),
+            ("Discard", Message::DiscardChanges),
+        )
+    ]
+    .into()
+}
@@ -1,66 +1,287 @@
 use iced::{
+    Alignment::Center,
     Element,
     Length::Fill,
+    Task,
     border::rounded,
-    widget::{column, container, horizontal_space, keyed_column, row, scrollable, text},
+    widget::{
+        center, column, container, horizontal_space, keyed_column, pick_list, row, scrollable,
+        stack, text, text_input,
+    },
 };
 use user_registry_lib::User;
 
 use crate::{
     constants::{
         BIG_TEXT_SIZE, BOLD_FONT, BUTTON_HEIGHT, BUTTON_PADDING, LARGE_TEXT_SIZE, MEDIUM_TEXT_SIZE,
-        NORMAL_PADDING, NORMAL_SPACING, NORMAL_TEXT_SIZE, OUTER_BORDER_RADIUS, SMALL_PADDING,
-        WINDOW_PADDING,
+        MIN_COLUMN_WIDTH, NORMAL_PADDING, NORMAL_SPACING, NORMAL_TEXT_SIZE, OUTER_BORDER_RADIUS,
+        SMALL_PADDING, WINDOW_PADDING,
     },
+    form::UserForm,
     message::Message,
+    page::Page,
+    preferences::Layout,
     state::State,
-    utils::{create_danger_button, create_primary_button},
+    storage, table,
+    utils::{create_danger_button, create_dialog, create_highlighted_text, create_primary_button},
 };
 
+const USERS_ID: &str = "users";
+
 pub fn title() -> String {
     String::from("Users")
 }
 
-pub fn view(state: &State) -> Element<Message> {
-    let mut users = keyed_column![].spacing(NORMAL_SPACING);
+/// Returns to the list of users, where it was scrolled to.
+pub fn open(state: &mut State) -> Task<Message> {
+    state.error = None;
+    state.page = Page::Main;
+    scrollable::scroll_to(scrollable::Id::new(USERS_ID), state.users_offset)
+}
+
+pub fn update(state: &mut State, message: Message) -> Task<Message> {
+    match message {
+        Message::SelectProfile(profile) => {
+            state.loading = true;
+            state.error = None;
+            return storage::switch_profile(state, profile);
+        }
+        Message::Search(search) => state.search = search,
+        Message::ScrollUsers(offset) => state.users_offset = offset,
+        Message::SetLayout(layout) => {
+            state.preferences.layout = layout;
+            return storage::save_preferences(&state.preferences);
+        }
+        Message::SortBy(column) => {
+            let sort = &mut state.preferences.sort;
+            sort.descending = sort.column == column && !sort.descending;
+            sort.column = column;
+            return storage::save_preferences(&state.preferences);
+        }
+        Message::StartResize(index) => state.resizing = Some((index, None)),
+        Message::ResizeColumn(position) => {
+            if let Some((index, last)) = &mut state.resizing {
+                if let Some(last) = last {
+                    let width = &mut state.preferences.column_widths[*index];
+                    *width = (*width + position.x - *last).max(MIN_COLUMN_WIDTH);
+                }
+                *last = Some(position.x);
+            }
+        }
+        Message::EndResize if state.resizing.is_some() => {
+            state.resizing = None;
+            return storage::save_preferences(&state.preferences);
+        }
+        Message::ShowUser(id) => {
+            state.error = None;
+            state.page = Page::Details(id);
+        }
+        Message::RemoveUser(id) => state.removing = Some(id),
+        Message::ConfirmRemove => {
+            let Some(id) = state.removing.take() else {
+                return Task::none();
+            };
+            if state.data.remove_user(id).is_none() {
+                return Task::none();
+            }
+
+            state.error = None;
+            return storage::save(state);
+        }
+        Message::CancelRemove => state.removing = None,
+        Message::ShowAddForm => {
+            state.form = UserForm::default();
+            state.error = None;
+            state.page = Page::Add;
+        }
+        _ => {}
+    }
+
+    Task::none()
+}
+
+pub fn view(state: &State) -> Element<'_, Message> {
+    let all_users = state.data.users();
+    let total = all_users.len();
+    let mut users: Vec<_> = all_users
+        .into_iter()
+        .filter(|(_, user)| user.matches(&state.search))
+        .collect();
+    let shown = users.len();
+    let sort = state.preferences.sort;
 
-    for (id, user) in state.data.users() {
-        users = users.push(id, create_user(id, user));
+    users.sort_by(|first, second| sort.column.compare(*first, *second));
+    if sort.descending {
+        users.reverse();
     }
 
-    column![
-        row![
-            text("Users").size(LARGE_TEXT_SIZE).width(Fill),
+    let mut header = row![text("Users").size(LARGE_TEXT_SIZE).width(Fill)].spacing(NORMAL_SPACING);
+
+    if state.read_only {
+        header = header.push(
+            container(
+                text("Read-only")
+                    .size(NORMAL_TEXT_SIZE)
+                    .style(text::secondary),
+            )
+            .center_y(BUTTON_HEIGHT),
+        );
+    } else if state.saving {
+        header = header.push(
+            container(
+                text("Saving…")
+                    .size(NORMAL_TEXT_SIZE)
+                    .style(text::secondary),
+            )
+            .center_y(BUTTON_HEIGHT),
+        );
+    }
+
+    if !state.profiles.is_empty() {
+        header = header.push(
             container(
-                create_primary_button(text("Add").size(BIG_TEXT_SIZE).center())
-                    .padding(BUTTON_PADDING)
-                    .on_press(Message::Null)
+                pick_list(
+                    state.profiles.as_slice(),
+                    state.profile.clone(),
+                    Message::SelectProfile,
+                )
+                .placeholder("Profile")
+                .text_size(NORMAL_TEXT_SIZE),
             )
-            .center_y(BUTTON_HEIGHT)
-        ],
-        scrollable(users).spacing(NORMAL_SPACING)
+            .center_y(BUTTON_HEIGHT),
+        );
+    }
+
+    let (layout, layout_label) = match state.preferences.layout {
+        Layout::Cards => (Layout::Table, "Table"),
+        Layout::Table => (Layout::Cards, "Cards"),
+    };
+
+    header = header.push(
+        container(
+            create_primary_button(text(layout_label).size(BIG_TEXT_SIZE).center())
+                .padding(BUTTON_PADDING)
+                .on_press(Message::SetLayout(layout)),
+        )
+        .center_y(BUTTON_HEIGHT),
+    );
+
+    header = header.push(
+        container(
+            create_primary_button(text("Add").size(BIG_TEXT_SIZE).center())
+                .padding(BUTTON_PADDING)
+                .on_press_maybe(
+                    (!state.loading && state.is_editable()).then_some(Message::ShowAddForm),
+                ),
+        )
+        .center_y(BUTTON_HEIGHT),
+    );
+
+    let count = match (state.search.is_empty(), total) {
+        (true, 1) => String::from("1 user"),
+        (true, total) => format!("{total} users"),
+        (false, 1) => format!("{shown} of 1 user"),
+        (false, total) => format!("{shown} of {total} users"),
+    };
+    let search = row![
+        text_input("Search by name, email or phone", &state.search)
+            .on_input(Message::Search)
+            .padding(SMALL_PADDING)
+            .width(Fill),
+        text(count).size(NORMAL_TEXT_SIZE),
     ]
     .spacing(NORMAL_SPACING)
-    .padding(WINDOW_PADDING)
-    .into()
+    .align_y(Center);
+
+    let mut page = column![header, search].spacing(NORMAL_SPACING);
+
+    if let Some(error) = &state.error {
+        page = page.push(text(error).size(NORMAL_TEXT_SIZE).style(text::danger));
+    }
+
+    let list = match state.preferences.layout {
+        _ if state.loading => center(text("Loading users…").size(MEDIUM_TEXT_SIZE)).into(),
+        Layout::Cards => {
+            let mut cards = keyed_column![].spacing(NORMAL_SPACING);
+
+            for (id, user) in users {
+                cards = cards.push(
+                    id,
+                    create_user(id, user, &state.search, state.is_editable()),
+                );
+            }
+
+            scrollable(cards)
+                .id(scrollable::Id::new(USERS_ID))
+                .on_scroll(|viewport| Message::ScrollUsers(viewport.absolute_offset()))
+                .spacing(NORMAL_SPACING)
+                .into()
+        }
+        Layout::Table => table::view(
+            &users,
+            &state.preferences,
+            &state.search,
+            state.is_editable(),
+            scrollable::Id::new(USERS_ID),
+        ),
+    };
+
+    let page = page.push(list).padding(WINDOW_PADDING);
+
+    match state
+        .removing
+        .and_then(|id| state.data.user(id).map(|user| (id, user)))
+    {
+        Some((id, user)) => stack![page, create_remove_dialog(id, user)].into(),
+        None => page.into(),
+    }
+}
+
+/// Creates a dialog over the page asking to confirm the removal of a user.
+fn create_remove_dialog(id: usize, user: &User) -> Element<'_, Message> {
+    create_dialog(
+        String::from("Remove user"),
+        format!(
+            "Remove {} {} (ID: {id}) from the registry?",
+            user.first_name, user.last_name
+        ),
+        ("Cancel", Message::CancelRemove),
+        ("Remove", Message::ConfirmRemove),
+    )
 }
 
-pub fn create_user(id: usize, user: &User) -> Element<Message> {
+pub fn create_user<'a>(
+    id: usize,
+    user: &'a User,
+    search: &str,
+    editable: bool,
+) -> Element<'a, Message> {
     container(row![
         horizontal_space().width(SMALL_PADDING),
         column![
-            text(format!("{} {}", user.first_name, user.last_name))
-                .size(MEDIUM_TEXT_SIZE)
-                .font(BOLD_FONT),
-            text(format!("ID: {id}"))
+            row![
+                create_highlighted_text(&user.first_name, search)
+                    .size(MEDIUM_TEXT_SIZE)
+                    .font(BOLD_FONT),
+                text(" ").size(MEDIUM_TEXT_SIZE),
+                create_highlighted_text(&user.last_name, search)
+                    .size(MEDIUM_TEXT_SIZE)
+                    .font(BOLD_FONT),
+            ],
+            row![
+                create_highlighted_text(&user.email, search),
+                text(" · "),
+                create_highlighted_text(&user.phone_number, search),
+            ],
+            text(format!("ID: {id}")),
         ]
         .width(Fill),
         container(row![
             create_primary_button(text("Show").size(NORMAL_TEXT_SIZE).center())
-                .on_press(Message::Null),
+                .on_press(Message::ShowUser(id)),
             horizontal_space().width(NORMAL_SPACING),
             create_danger_button(text("Remove").size(NORMAL_TEXT_SIZE).center())
-                .on_press(Message::Null),
+                .on_press_maybe(editable.then_some(Message::RemoveUser(id))),
         ])
         .center_y(BUTTON_HEIGHT)
     ])
@@ -0,0 +1,191 @@
+use std::{cmp::Ordering, fs, io, path::PathBuf};
+
+use serde::{Deserialize, Serialize};
+use user_registry_lib::{Config, Field, User};
+
+use crate::{Error, constants::DEFAULT_COLUMN_WIDTHS};
+
+/// How the list of users is laid out.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Layout {
+    #[default]
+    Cards,
+    Table,
+}
+
+/// A column of the table of users.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Column {
+    #[default]
+    Id,
+    Field(Field),
+}
+
+impl Column {
+    /// Every column, in the order they are shown.
+    pub const ALL: [Self; 5] = [
+        Self::Id,
+        Self::Field(Field::FirstName),
+        Self::Field(Field::LastName),
+        Self::Field(Field::Email),
+        Self::Field(Field::PhoneNumber),
+    ];
+
+    /// Returns the title of the column.
+    pub fn title(self) -> String {
+        match self {
+            Self::Id => String::from("ID"),
+            Self::Field(field) => field.to_string(),
+        }
+    }
+
+    /// Compares two users by this column, ignoring case.
+    pub fn compare(
+        self,
+        (id, user): (usize, &User),
+        (other_id, other): (usize, &User),
+    ) -> Ordering {
+        match self {
+            Self::Id => id.cmp(&other_id),
+            Self::Field(field) => field
+                .value(user)
+                .to_lowercase()
+                .cmp(&field.value(other).to_lowercase())
+                .then(id.cmp(&other_id)),
+        }
+    }
+}
+
+/// The order of the list of users.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Sort {
+    pub column: Column,
+    pub descending: bool,
+}
+
+/// Settings of the GUI kept across runs, in `gui.json` next to the
+/// configuration file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Preferences {
+    pub layout: Layout,
+    pub sort: Sort,
+
+    /// The widths of the columns of the table, in the order of
+    /// [`Column::ALL`].
+    pub column_widths: [f32; 5],
+}
+
+impl Default for Preferences {
+    fn default() -> Self {
+        Self {
+            layout: Layout::default(),
+            sort: Sort::default(),
+            column_widths: DEFAULT_COLUMN_WIDTHS,
+        }
+    }
+}
+
+impl Preferences {
+    /// Returns the path of the preferences file.
+    pub fn path() -> Option<PathBuf> {
+        Config::path().map(|path| path.with_file_name("gui.json"))
+    }
+
+    /// Loads the preferences, falling back to the defaults if the file is
+    /// missing or invalid.
+    pub fn load() -> Self {
+        Self::path()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+
+    /// Saves the preferences, creating the folder of the file if needed.
+    pub fn save(&self) -> Result<(), Error> {
+        let path = Self::path()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "No preferences file path."))?;
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        fs::write(
+            path,
+            serde_json::to_string_pretty(self).map_err(io::Error::from)?,
+        )?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Helper function to create a [`User`] with data for testing purposes.
+    fn user(first_name: &str, last_name: &str) -> User {
+        User {
+            first_name: String::from(first_name),
+            last_name: String::from(last_name),
+            email: String::from("firstEmail"),
+            phone_number: String::from("0123456789"),
+        }
+    }
+
+    /// Tests comparing [`User`]s by a column of the table.
+    ///
+    /// This test ensures that:
+    /// - Fields are compared ignoring case, including letters that aren't
+    ///   ASCII.
+    /// - Users with equal fields are ordered by ID.
+    /// - The ID column compares IDs only.
+    #[test]
+    fn compare_users_by_column() {
+        let ann = user("ann", "Émile");
+        let bob = user("Bob", "émile");
+        let first_name = Column::Field(Field::FirstName);
+        let last_name = Column::Field(Field::LastName);
+
+        assert_eq!(first_name.compare((5, &ann), (1, &bob)), Ordering::Less);
+        assert_eq!(first_name.compare((1, &bob), (5, &ann)), Ordering::Greater);
+        assert_eq!(last_name.compare((1, &bob), (5, &ann)), Ordering::Less);
+        assert_eq!(last_name.compare((5, &ann), (5, &bob)), Ordering::Equal);
+        assert_eq!(Column::Id.compare((5, &ann), (1, &bob)), Ordering::Greater);
+    }
+
+    /// Tests saving and reading [`Preferences`] as JSON.
+    ///
+    /// This test ensures that:
+    /// - [`Preferences`] read back equal to the ones written.
+    /// - Layouts and columns are written in snake case.
+    /// - Missing settings fall back to their defaults.
+    #[test]
+    fn preferences_round_trip() {
+        let preferences = Preferences {
+            layout: Layout::Table,
+            sort: Sort {
+                column: Column::Field(Field::PhoneNumber),
+                descending: true,
+            },
+            column_widths: [10.0, 20.0, 30.0, 40.0, 50.0],
+        };
+
+        let json = serde_json::to_string(&preferences).unwrap();
+        assert!(json.contains(r#""layout":"table""#));
+        assert!(json.contains(r#""column":{"field":"phone_number"}"#));
+        assert_eq!(
+            serde_json::from_str::<Preferences>(&json).unwrap(),
+            preferences
+        );
+
+        assert_eq!(
+            serde_json::from_str::<Preferences>(r#"{"layout": "table"}"#).unwrap(),
+            Preferences {
+                layout: Layout::Table,
+                ..Preferences::default()
+            }
+        );
+    }
+}
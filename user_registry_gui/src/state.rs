@@ -1,22 +1,88 @@
-use std::{io, path::Path};
+use std::path::{Path, PathBuf};
 
-use user_registry_lib::{Data, command::read_data};
+use iced::widget::scrollable::AbsoluteOffset;
+use user_registry_lib::{ConfigFile, Data};
 
-use crate::page::Page;
+use crate::{Error, form::UserForm, page::Page, preferences::Preferences};
 
 #[derive(Default)]
 pub struct State {
     pub page: Page,
     pub data: Data,
+    pub data_file: PathBuf,
+    pub profiles: Vec<String>,
+    pub profile: Option<String>,
+    pub error: Option<String>,
+    pub form: UserForm,
+
+    /// The text that the list of users is filtered by.
+    pub search: String,
+
+    /// The scroll position of the list of users, kept while other pages are
+    /// shown.
+    pub users_offset: AbsoluteOffset,
+
+    /// The ID of the user waiting for the removal to be confirmed.
+    pub removing: Option<usize>,
+
+    pub preferences: Preferences,
+
+    /// The index of the table column being resized, and the last position of
+    /// the mouse while dragging its edge.
+    pub resizing: Option<(usize, Option<f32>)>,
+
+    /// The users read after the data file changed on disk, waiting for the
+    /// user to choose between them and the unsaved changes of a form.
+    pub reloaded: Option<Data>,
+
+    /// Whether the users are only shown, without any way to change them.
+    pub read_only: bool,
+
+    /// Whether the data file couldn't be read, in which case saving would
+    /// replace it with the few users shown.
+    pub load_failed: bool,
+
+    /// Whether the users are being loaded.
+    pub loading: bool,
+
+    /// The users as they are in the data file, restored when a save fails.
+    pub saved: Data,
+
+    /// Whether the users are being saved, and whether they changed since.
+    pub saving: bool,
+    pub save_pending: bool,
+
+    /// Whether the data file changed on disk while the users were being
+    /// saved, so that it must be read again once they are.
+    pub changed_while_saving: bool,
+
+    /// The profile to switch to once the users are saved.
+    pub switching: Option<String>,
 }
 
 impl State {
-    pub fn with_data_file<P: AsRef<Path>>(data_file: P) -> Result<Self, io::Error> {
-        let data = read_data(data_file)?;
+    /// Creates the state of the GUI for the data file at `data_file`, whose
+    /// users are then loaded in the background.
+    pub fn with_data_file<P: AsRef<Path>>(
+        data_file: P,
+        profile: Option<String>,
+        read_only: bool,
+    ) -> Result<Self, Error> {
+        let profiles = ConfigFile::load()?.profiles().keys().cloned().collect();
 
         Ok(Self {
-            data,
+            data_file: data_file.as_ref().to_path_buf(),
+            profiles,
+            profile,
+            preferences: Preferences::load(),
+            read_only,
+            loading: true,
             ..Default::default()
         })
     }
+
+    /// Returns whether the users can be changed.
+    pub fn is_editable(&self) -> bool {
+        !self.read_only && !self.load_failed
+    }
 }
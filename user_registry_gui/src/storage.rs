@@ -0,0 +1,162 @@
+use std::{mem, path::PathBuf};
+
+use iced::Task;
+use user_registry_lib::{
+    Config,
+    command::{read_data, save_data},
+};
+
+use crate::{Error, message::Message, preferences::Preferences, state::State, watch};
+
+/// Reads the users of the data file at `data_file` in the background.
+pub fn load(data_file: PathBuf) -> Task<Message> {
+    Task::perform(
+        async move { read_data(&data_file).map_err(|err| Error::from(err).to_string()) },
+        Message::Loaded,
+    )
+}
+
+/// Reads the data file of the profile called `profile` in the background,
+/// creating its folder unless `read_only`.
+fn load_profile(profile: String, read_only: bool) -> Task<Message> {
+    Task::perform(
+        async move {
+            let config = Config::load(Some(&profile))?;
+            let data_file = if read_only {
+                config.find_data_file(None)?
+            } else {
+                config.prepare_data_file(None)?
+            };
+            let data = read_data(&data_file)?;
+
+            Ok::<_, Error>((profile, data_file, data))
+        },
+        |result| Message::ProfileLoaded(result.map_err(|err| err.to_string())),
+    )
+}
+
+/// Switches to the profile called `profile`, after the users of the current
+/// one are saved.
+pub fn switch_profile(state: &mut State, profile: String) -> Task<Message> {
+    if state.saving {
+        state.switching = Some(profile);
+        return Task::none();
+    }
+
+    load_profile(profile, state.read_only)
+}
+
+/// Saves the users to the data file in the background.
+///
+/// Saves happen one at a time, so that an older save never overwrites a newer
+/// one: while a save is running, the next one waits for it to finish. If a
+/// save fails, the users go back to the last ones saved. Nothing is saved
+/// while the users can't be changed.
+pub fn save(state: &mut State) -> Task<Message> {
+    if !state.is_editable() {
+        return Task::none();
+    }
+
+    if state.saving {
+        state.save_pending = true;
+        return Task::none();
+    }
+
+    state.saving = true;
+    let data_file = state.data_file.clone();
+    let data = state.data.clone();
+
+    Task::perform(
+        async move {
+            save_data(&data_file, &data)
+                .map(|()| data)
+                .map_err(|err| Error::from(err).to_string())
+        },
+        Message::Saved,
+    )
+}
+
+/// Goes on once every save is done: switches to the profile selected while
+/// saving, or else reads the data file again if it changed meanwhile.
+fn finish_saving(state: &mut State) -> Task<Message> {
+    let changed = mem::take(&mut state.changed_while_saving);
+
+    if let Some(profile) = state.switching.take() {
+        return load_profile(profile, state.read_only);
+    }
+
+    if changed {
+        return watch::read(state.data_file.clone());
+    }
+
+    Task::none()
+}
+
+/// Saves the preferences in the background.
+pub fn save_preferences(preferences: &Preferences) -> Task<Message> {
+    let preferences = preferences.clone();
+
+    Task::perform(
+        async move { preferences.save().map_err(|err| err.to_string()) },
+        Message::PreferencesSaved,
+    )
+}
+
+/// Handles the results of loading and saving.
+pub fn update(state: &mut State, message: Message) -> Task<Message> {
+    match message {
+        Message::Loaded(Ok(data)) => {
+            state.loading = false;
+            state.load_failed = false;
+            state.saved = data.clone();
+            state.data = data;
+        }
+        Message::Loaded(Err(err)) => {
+            state.loading = false;
+            state.load_failed = true;
+            state.error = Some(format!(
+                "Couldn't read users: {err}\nRun `cli doctor` to check the data file, or \
+                 `cli doctor --repair` to fix it. Changes are disabled until then."
+            ));
+        }
+        Message::ProfileLoaded(Ok((profile, data_file, data))) => {
+            state.loading = false;
+            state.load_failed = false;
+            state.saved = data.clone();
+            state.data = data;
+            state.data_file = data_file;
+            state.profile = Some(profile);
+        }
+        Message::ProfileLoaded(Err(err)) => {
+            state.loading = false;
+            state.error = Some(format!("Couldn't open profile: {err}"));
+        }
+        Message::Saved(Ok(data)) => {
+            state.saving = false;
+            state.saved = data;
+
+            if state.save_pending {
+                state.save_pending = false;
+                return save(state);
+            }
+
+            return finish_saving(state);
+        }
+        Message::Saved(Err(err)) => {
+            // The changes waiting to be saved were made on top of the failed
+            // ones, so they are undone too.
+            state.saving = false;
+            state.save_pending = false;
+            state.data = state.saved.clone();
+            state.error = Some(format!("Couldn't save users: {err}"));
+
+            return finish_saving(state);
+        }
+        Message::PreferencesSaved(Err(err)) => {
+            state.error = Some(format!("Couldn't save preferences: {err}"));
+        }
+        _ => {}
+    }
+
+    Task::none()
+}
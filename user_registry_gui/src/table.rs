@@ -0,0 +1,140 @@
+use iced::{
+    Alignment::Center,
+    Element,
+    Length::Fill,
+    border::rounded,
+    mouse::Interaction,
+    widget::{
+        Row, button, column, container, horizontal_space, keyed_column, mouse_area, row,
+        scrollable, text, vertical_rule,
+    },
+};
+use user_registry_lib::User;
+
+use crate::{
+    constants::{
+        BOLD_FONT, BUTTON_HEIGHT, INNER_BORDER_RADIUS, NORMAL_SPACING, NORMAL_TEXT_SIZE,
+        RESIZE_HANDLE_WIDTH, SMALL_PADDING,
+    },
+    message::Message,
+    preferences::{Column, Preferences},
+    utils::{create_danger_button, create_highlighted_text, create_primary_button},
+};
+
+fn create_header(preferences: &Preferences) -> Element<'_, Message> {
+    let mut header = Row::new();
+
+    for (index, column) in Column::ALL.into_iter().enumerate() {
+        let arrow = match preferences.sort {
+            sort if sort.column != column => "",
+            sort if sort.descending => " ▼",
+            _ => " ▲",
+        };
+
+        header = header
+            .push(
+                button(
+                    text(format!("{}{arrow}", column.title()))
+                        .size(NORMAL_TEXT_SIZE)
+                        .font(BOLD_FONT),
+                )
+                .style(button::text)
+                .padding(SMALL_PADDING)
+                .width(preferences.column_widths[index])
+                .on_press(Message::SortBy(column)),
+            )
+            .push(
+                mouse_area(
+                    container(vertical_rule(1))
+                        .center_x(RESIZE_HANDLE_WIDTH)
+                        .height(BUTTON_HEIGHT),
+                )
+                .interaction(Interaction::ResizingHorizontally)
+                .on_press(Message::StartResize(index)),
+            );
+    }
+
+    // Lines the headers up with the cells of the rows.
+    header.padding([0, SMALL_PADDING]).into()
+}
+
+fn create_row<'a>(
+    id: usize,
+    user: &'a User,
+    preferences: &Preferences,
+    search: &str,
+    editable: bool,
+) -> Element<'a, Message> {
+    let mut cells = Row::new();
+
+    for (index, column) in Column::ALL.into_iter().enumerate() {
+        let cell: Element<'a, Message> = match column {
+            Column::Id => text(id.to_string()).size(NORMAL_TEXT_SIZE).into(),
+            Column::Field(field) => create_highlighted_text(field.value(user), search)
+                .size(NORMAL_TEXT_SIZE)
+                .into(),
+        };
+
+        cells = cells.push(
+            container(cell)
+                .padding(SMALL_PADDING)
+                .width(preferences.column_widths[index] + RESIZE_HANDLE_WIDTH)
+                .clip(true),
+        );
+    }
+
+    container(
+        cells
+            .push(horizontal_space())
+            .push(
+                row![
+                    create_primary_button(text("Show").size(NORMAL_TEXT_SIZE).center())
+                        .on_press(Message::ShowUser(id)),
+                    create_danger_button(text("Remove").size(NORMAL_TEXT_SIZE).center())
+                        .on_press_maybe(editable.then_some(Message::RemoveUser(id))),
+                ]
+                .spacing(NORMAL_SPACING),
+            )
+            .align_y(Center),
+    )
+    .style(|theme| {
+        container::Style::default()
+            .background(theme.extended_palette().background.weak.color)
+            .border(rounded(INNER_BORDER_RADIUS))
+    })
+    .padding(SMALL_PADDING)
+    .width(Fill)
+    .into()
+}
+
+/// Shows the `users` as a table with a column per field, whose headers sort
+/// the table when pressed and resize the columns when dragged by their edge.
+/// Unless `editable`, the users can't be removed.
+pub fn view<'a>(
+    users: &[(usize, &'a User)],
+    preferences: &'a Preferences,
+    search: &str,
+    editable: bool,
+    scroll_id: scrollable::Id,
+) -> Element<'a, Message> {
+    let mut rows = keyed_column![].spacing(SMALL_PADDING);
+
+    for &(id, user) in users {
+        rows = rows.push(id, create_row(id, user, preferences, search, editable));
+    }
+
+    mouse_area(
+        column![
+            create_header(preferences),
+            scrollable(rows)
+                .id(scroll_id)
+                .on_scroll(|viewport| Message::ScrollUsers(viewport.absolute_offset()))
+                .spacing(NORMAL_SPACING),
+        ]
+        .spacing(SMALL_PADDING),
+    )
+    .on_move(Message::ResizeColumn)
+    .on_release(Message::EndResize)
+    .on_exit(Message::EndResize)
+    .into()
+}
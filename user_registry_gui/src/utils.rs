@@ -1,10 +1,18 @@
+use std::ops::Range;
+
 use iced::{
-    Element,
+    Color, Element,
     border::rounded,
-    widget::{Button, button},
+    widget::{
+        Button, button, center, column, container, horizontal_space, mouse_area, opaque, rich_text,
+        row, span, text,
+    },
 };
 
-use crate::constants::{BUTTON_HEIGHT, BUTTON_WIDTH, INNER_BORDER_RADIUS};
+use crate::constants::{
+    BIG_TEXT_SIZE, BOLD_FONT, BUTTON_HEIGHT, BUTTON_WIDTH, DIALOG_WIDTH, HIGHLIGHT_COLOR,
+    INNER_BORDER_RADIUS, NORMAL_SPACING, NORMAL_TEXT_SIZE, OUTER_BORDER_RADIUS, WINDOW_PADDING,
+};
 
 pub fn create_button<'a, T>(content: impl Into<Element<'a, T>>) -> Button<'a, T> {
     button(content).width(BUTTON_WIDTH).height(BUTTON_HEIGHT)
@@ -23,3 +31,139 @@ pub fn create_danger_button<'a, T>(content: impl Into<Element<'a, T>>) -> Button
         ..button::danger(theme, status)
     })
 }
+
+/// Creates a dialog to show over a page, asking a `question` that is answered
+/// by the `confirm` button, or dismissed by the `cancel` button or by pressing
+/// outside of the dialog.
+pub fn create_dialog<'a, T: Clone + 'a>(
+    title: String,
+    question: String,
+    cancel: (&'a str, T),
+    confirm: (&'a str, T),
+) -> Element<'a, T> {
+    let (cancel_label, on_cancel) = cancel;
+    let (confirm_label, on_confirm) = confirm;
+
+    let dialog = container(
+        column![
+            text(title).size(BIG_TEXT_SIZE).font(BOLD_FONT),
+            text(question).size(NORMAL_TEXT_SIZE),
+            row![
+                horizontal_space(),
+                create_primary_button(text(cancel_label).size(NORMAL_TEXT_SIZE).center())
+                    .on_press(on_cancel.clone()),
+                create_danger_button(text(confirm_label).size(NORMAL_TEXT_SIZE).center())
+                    .on_press(on_confirm),
+            ]
+            .spacing(NORMAL_SPACING),
+        ]
+        .spacing(NORMAL_SPACING),
+    )
+    .style(|theme| {
+        container::Style::default()
+            .background(theme.extended_palette().background.base.color)
+            .border(rounded(OUTER_BORDER_RADIUS))
+    })
+    .padding(WINDOW_PADDING)
+    .width(DIALOG_WIDTH);
+
+    opaque(
+        mouse_area(center(opaque(dialog)).style(|_theme| {
+            container::Style::default().background(Color {
+                a: 0.8,
+                ..Color::BLACK
+            })
+        }))
+        .on_press(on_cancel),
+    )
+}
+
+/// Returns the byte ranges of `value` that match `query`, ignoring case.
+fn find_matches(value: &str, query: &str) -> Vec<Range<usize>> {
+    let mut matches = Vec::new();
+
+    if query.is_empty() {
+        return matches;
+    }
+
+    let mut start = 0;
+    while start < value.len() {
+        let mut chars = value[start..].char_indices();
+        let matched = query.chars().all(|expected| {
+            chars
+                .next()
+                .is_some_and(|(_, actual)| actual.to_lowercase().eq(expected.to_lowercase()))
+        });
+
+        if matched {
+            let end = start + chars.offset();
+            matches.push(start..end);
+            start = end;
+        } else {
+            start += value[start..].chars().next().map_or(1, char::len_utf8);
+        }
+    }
+
+    matches
+}
+
+/// Creates a text showing `value`, with the parts that match `query`
+/// highlighted.
+pub fn create_highlighted_text<'a, T: Clone + 'static>(
+    value: &'a str,
+    query: &str,
+) -> text::Rich<'a, T> {
+    let mut spans = Vec::new();
+    let mut start = 0;
+
+    for range in find_matches(value, query) {
+        spans.push(span(&value[start..range.start]));
+        spans.push(
+            span(&value[range.clone()])
+                .color(Color::BLACK)
+                .background(HIGHLIGHT_COLOR),
+        );
+        start = range.end;
+    }
+
+    spans.push(span(&value[start..]));
+    rich_text(spans)
+}
+
+#[cfg(test)]
+// The expected matches are lists of ranges, even when there is a single one.
+#[allow(clippy::single_range_in_vec_init)]
+mod tests {
+    use super::*;
+
+    /// Tests finding the parts of a value that match a search query.
+    ///
+    /// This test ensures that:
+    /// - Matches ignore case in both the value and the query.
+    /// - Every match is found, without overlapping the previous one.
+    /// - An empty query or a query longer than the value matches nothing.
+    #[test]
+    fn find_matches_ignoring_case() {
+        assert_eq!(find_matches("John Doe", "JOHN"), [0..4]);
+        assert_eq!(find_matches("DOE doe", "doe"), [0..3, 4..7]);
+        assert_eq!(find_matches("aaaa", "aa"), [0..2, 2..4]);
+        assert_eq!(find_matches("John", ""), []);
+        assert_eq!(find_matches("Jo", "John"), []);
+        assert_eq!(find_matches("John", "x"), []);
+    }
+
+    /// Tests finding matches in text that isn't ASCII.
+    ///
+    /// This test ensures that:
+    /// - The ranges are byte ranges that fall on character boundaries.
+    /// - Characters that aren't ASCII match regardless of case.
+    #[test]
+    fn find_matches_in_multibyte_text() {
+        let value = "José Muñoz";
+
+        assert_eq!(find_matches(value, "sé"), [2..5]);
+        assert_eq!(&value[2..5], "sé");
+        assert_eq!(find_matches(value, "ÑOZ"), [8..12]);
+        assert_eq!(find_matches("ÉÉé", "é"), [0..2, 2..4, 4..6]);
+    }
+}
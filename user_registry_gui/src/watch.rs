@@ -0,0 +1,158 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    thread,
+    time::SystemTime,
+};
+
+use iced::{Element, Subscription, Task, stream, widget::stack};
+use user_registry_lib::{Data, command::read_data};
+
+use crate::{
+    Error, constants::WATCH_INTERVAL, form::UserForm, message::Message, page::Page, pages,
+    state::State, utils::create_dialog,
+};
+
+/// Returns when the file at `path` was last modified and its size, if it
+/// exists.
+fn version(path: &Path) -> Option<(SystemTime, u64)> {
+    let metadata = fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+/// Watches the data file at `data_file`, producing a
+/// [`Message::DataFileChanged`] whenever it is modified, created or removed.
+///
+/// The file is polled from a thread, which stops at the next poll once the
+/// subscription is dropped.
+pub fn subscription(data_file: PathBuf) -> Subscription<Message> {
+    Subscription::run_with_id(
+        data_file.clone(),
+        stream::channel(1, move |mut output| async move {
+            thread::spawn(move || {
+                let mut last = version(&data_file);
+
+                // The channel is closed once the subscription is dropped.
+                while !output.is_closed() {
+                    thread::sleep(WATCH_INTERVAL);
+
+                    let current = version(&data_file);
+                    if current == last {
+                        continue;
+                    }
+                    last = current;
+
+                    // A full channel already holds a change to handle, and a
+                    // closed one ends the loop.
+                    let _ = output.try_send(Message::DataFileChanged);
+                }
+            });
+        }),
+    )
+}
+
+/// Replaces the users with `data`, leaving forms and pages about users that
+/// don't exist anymore.
+fn reload(state: &mut State, data: Data) -> Task<Message> {
+    state.saved = data.clone();
+    state.data = data;
+    state.reloaded = None;
+
+    // The data file was fixed since it failed to load.
+    if state.load_failed {
+        state.load_failed = false;
+        state.error = None;
+    }
+
+    match state.page {
+        Page::Add => Task::none(),
+        Page::Details(id) | Page::Edit(id) if state.data.user(id).is_none() => {
+            state.form = UserForm::default();
+            pages::main::open(state)
+        }
+        Page::Edit(id) => {
+            pages::edit::open(state, id);
+            Task::none()
+        }
+        Page::Main | Page::Details(_) => Task::none(),
+    }
+}
+
+/// Reads the data file at `data_file` again in the background.
+pub fn read(data_file: PathBuf) -> Task<Message> {
+    Task::perform(
+        async move { read_data(&data_file).map_err(|err| Error::from(err).to_string()) },
+        Message::Reloaded,
+    )
+}
+
+/// Handles the changes of the data file on disk.
+pub fn update(state: &mut State, message: Message) -> Task<Message> {
+    match message {
+        // A running load reads the new contents anyway.
+        Message::DataFileChanged if state.loading => Task::none(),
+        // The file is read again once the save is done, so that the change
+        // isn't confused with the save.
+        Message::DataFileChanged | Message::Reloaded(_) if state.saving => {
+            state.changed_while_saving = true;
+            Task::none()
+        }
+        Message::DataFileChanged => read(state.data_file.clone()),
+        Message::Reloaded(Ok(data)) => {
+            // Saves of the GUI itself change the file too.
+            if !state.load_failed && data.users() == state.saved.users() {
+                return Task::none();
+            }
+
+            if state.form.is_dirty() && matches!(state.page, Page::Add | Page::Edit(_)) {
+                state.reloaded = Some(data);
+                return Task::none();
+            }
+
+            reload(state, data)
+        }
+        Message::Reloaded(Err(err)) => {
+            state.error = Some(format!("Couldn't reload users: {err}"));
+            Task::none()
+        }
+        Message::ReloadData => match state.reloaded.take() {
+            Some(data) => {
+                state.form.user = state.form.original.clone();
+                reload(state, data)
+            }
+            None => Task::none(),
+        },
+        Message::KeepChanges => {
+            // The changes are kept in the form, to be saved over the new
+            // users.
+            if let Some(data) = state.reloaded.take() {
+                state.saved = data.clone();
+                state.data = data;
+            }
+            Task::none()
+        }
+        _ => Task::none(),
+    }
+}
+
+/// Shows the `page`, with a dialog over it if the data file changed while a
+/// form has unsaved changes.
+pub fn view<'a>(state: &State, page: Element<'a, Message>) -> Element<'a, Message> {
+    if state.reloaded.is_none() {
+        return page;
+    }
+
+    stack![
+        page,
+        create_dialog(
+            String::from("Users changed on disk"),
+            String::from(
+                "Another program changed the data file. Reload it and discard your unsaved \
+                 changes, or keep them to save them over the new users?"
+            ),
+            ("Keep mine", Message::KeepChanges),
+            ("Reload", Message::ReloadData),
+        )
+    ]
+    .into()
+}
@@ -1,14 +1,24 @@
-use std::{fs, io, path::Path};
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
 
+pub mod backup;
 pub mod data;
 pub mod error;
+pub mod ldif;
+pub mod template;
 pub mod write;
 
 pub use data::{read_data, save_data};
 pub use error::Error;
-pub use write::{show, write_user};
+pub use template::Template;
+pub use write::{
+    show, show_data, show_data_with_template, show_with_template, write_diff, write_user,
+};
 
-use crate::User;
+use crate::{ConflictPolicy, Data, Diagnosis, Diff, MergeRecord, Patch, User};
 
 /// Adds a new [`User`] to the data file.
 ///
@@ -99,24 +109,262 @@ pub fn remove<P: AsRef<Path>>(path: P, id: usize) -> Result<User, Error> {
     user.ok_or(Error::UserNotFound(id))
 }
 
-/// Resets the data file by removing it.
+/// Reads the [`Data`] of the data file `other` that another one is compared or
+/// merged with, failing if it doesn't exist rather than reading it as an empty
+/// registry.
 ///
-/// This function deletes the file at the given `path`, effectively resetting
-/// the [`Data`] stored in the file.
+/// [`Data`]: crate::Data
+fn read_other_data(other: &Path) -> Result<Data, io::Error> {
+    if !other.is_file() {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("The data file {} doesn't exist.", other.display()),
+        ));
+    }
+
+    read_data(other)
+}
+
+/// Computes the differences between two data files.
+///
+/// This function reads the [`Data`] from the files at `path` and `other`, and
+/// returns the [`Diff`] needed to go from the former to the latter.
+///
+/// # Errors
+/// This function may return an error if `other` doesn't exist or if reading
+/// from either file fails (e.g., due to file access issues or invalid
+/// contents).
+///
+/// # Examples
+/// ```rust
+/// # use user_registry_lib::command::diff;
+/// fn compare() {
+///     let diff = diff("users.json", "edited.json").unwrap();
+///     println!("{} users were added", diff.added.len());
+/// }
+/// ```
+/// [`Data`]: crate::Data
+pub fn diff<P: AsRef<Path>, Q: AsRef<Path>>(path: P, other: Q) -> Result<Diff, io::Error> {
+    let data = read_data(&path)?;
+    let other = read_other_data(other.as_ref())?;
+
+    Ok(Diff::new(&data, &other))
+}
+
+/// Merges the [`User`]s of another data file into the data file.
+///
+/// This function reads the [`Data`] from the files at `path` and `other`,
+/// merges the latter into the former with [`Data::merge`] using the given
+/// conflict `policy`, and saves the result back to `path`. If `dry_run` is
+/// `true`, nothing is saved, but the returned [`MergeRecord`]s still describe
+/// what would have happened to every [`User`] of `other`.
+///
+/// # Errors
+/// This function may return an error if:
+/// - `other` doesn't exist.
+/// - Reading from either file fails (e.g., due to file access issues).
+/// - Writing to the file fails (e.g., due to insufficient permissions).
+///
+/// # Examples
+/// ```rust
+/// # use user_registry_lib::{command::merge, ConflictPolicy};
+/// fn consolidate() {
+///     let records = merge("users.json", "sales.json", ConflictPolicy::KeepBoth, false).unwrap();
+///
+///     for record in records {
+///         println!("{record}");
+///     }
+/// }
+/// ```
+/// [`Data`]: crate::Data
+/// [`Data::merge`]: crate::Data::merge
+pub fn merge<P: AsRef<Path>, Q: AsRef<Path>>(
+    path: P,
+    other: Q,
+    policy: ConflictPolicy,
+    dry_run: bool,
+) -> Result<Vec<MergeRecord>, io::Error> {
+    let mut data = read_data(&path)?;
+    let other = read_other_data(other.as_ref())?;
+    let records = data.merge(&other, policy);
+
+    if !dry_run {
+        save_data(&path, &data)?;
+    }
+
+    Ok(records)
+}
+
+/// Applies a JSON Patch file to the data file.
+///
+/// This function reads the [`Data`] from the file at `path` and the [`Patch`]
+/// from the file at `patch`, applies the latter with [`Data::apply_patch`],
+/// and saves the result back to `path`. The data file is only written if every
+/// operation succeeds, so a failing [`Patch`] leaves it untouched.
 ///
 /// # Errors
-/// This function may return an error if removing the file fails (e.g., the file
-/// doesn't exist or there are permission issues).
+/// This function may return an error if:
+/// - Reading from either file fails, or the patch file is not a valid JSON
+///   Patch document.
+/// - Any operation of the [`Patch`] fails, or the result is not a valid
+///   registry.
+/// - Writing to the file fails (e.g., due to insufficient permissions).
+///
+/// # Examples
+/// ```rust
+/// # use user_registry_lib::command::patch;
+/// fn apply() {
+///     patch("users.json", "changes.json").unwrap();
+/// }
+/// ```
+/// [`Data`]: crate::Data
+/// [`Data::apply_patch`]: crate::Data::apply_patch
+pub fn patch<P: AsRef<Path>, Q: AsRef<Path>>(path: P, patch: Q) -> Result<(), Error> {
+    let mut data = read_data(&path)?;
+    let patch: Patch =
+        serde_json::from_str(&fs::read_to_string(patch)?).map_err(io::Error::from)?;
+
+    data.apply_patch(&patch)?;
+    save_data(&path, &data)?;
+
+    Ok(())
+}
+
+/// Resets the data file by moving it to a timestamped backup next to it.
+///
+/// The backup is named after the data file and the current time, as returned
+/// by [`backup::backup_path`]. If the file doesn't exist or holds no
+/// [`User`]s, there is nothing to reset and nothing is backed up.
+///
+/// Returns the path of the backup, if one was made.
+///
+/// # Errors
+/// This function may return an error if reading the file fails or if moving it
+/// fails (e.g., there are permission issues).
 ///
 /// # Examples
 /// ```rust
 /// # use user_registry_lib::{command::reset};
 /// fn reset_data() {
 ///     let path = "users.json";
-///     reset(path).unwrap();
+///     if let Some(backup) = reset(path).unwrap() {
+///         println!("The old users were moved to {}.", backup.display());
+///     }
 /// }
 /// ```
-/// [`Data`]: crate::Data
-pub fn reset<P: AsRef<Path>>(path: P) -> Result<(), io::Error> {
-    fs::remove_file(path)
+/// [`User`]: crate::User
+pub fn reset<P: AsRef<Path>>(path: P) -> Result<Option<PathBuf>, io::Error> {
+    let path = path.as_ref();
+
+    if path.exists() && read_data(path)?.users().is_empty() {
+        return Ok(None);
+    }
+
+    force_reset(path)
+}
+
+/// Resets the data file without reading it, by moving it to a timestamped
+/// backup next to it.
+///
+/// Unlike [`reset`], the contents of the file are not parsed, so a damaged
+/// data file can be reset. The backup is named as for [`reset`]. If the file
+/// doesn't exist, there is nothing to reset and nothing is backed up.
+///
+/// Returns the path of the backup, if one was made.
+///
+/// # Errors
+/// This function may return an error if moving the file fails (e.g., there
+/// are permission issues).
+///
+/// # Examples
+/// ```rust
+/// # use user_registry_lib::{command::force_reset};
+/// fn reset_damaged_data() {
+///     let path = "users.json";
+///     if let Some(backup) = force_reset(path).unwrap() {
+///         println!("The damaged file was moved to {}.", backup.display());
+///     }
+/// }
+/// ```
+pub fn force_reset<P: AsRef<Path>>(path: P) -> Result<Option<PathBuf>, io::Error> {
+    let path = path.as_ref();
+
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let backup = backup::backup_path(path, SystemTime::now());
+    fs::rename(path, &backup)?;
+
+    Ok(Some(backup))
+}
+
+/// Copies the data file to a timestamped backup next to it, before a reset
+/// registry is saved over it.
+///
+/// The backup is named as for [`reset`]. If the file doesn't exist or holds
+/// no [`User`]s, there is nothing to lose and nothing is backed up.
+///
+/// Returns the path of the backup, if one was made.
+///
+/// # Errors
+/// This function may return an error if reading the file fails or if copying
+/// it fails (e.g., there are permission issues).
+///
+/// # Examples
+/// ```rust
+/// # use user_registry_lib::{Data, command::{back_up, save_data}};
+/// fn save_reset_data() {
+///     let path = "users.json";
+///     if let Some(backup) = back_up(path).unwrap() {
+///         println!("The old users were copied to {}.", backup.display());
+///     }
+///     save_data(path, &Data::new()).unwrap();
+/// }
+/// ```
+pub fn back_up<P: AsRef<Path>>(path: P) -> Result<Option<PathBuf>, io::Error> {
+    let path = path.as_ref();
+
+    if !path.exists() || read_data(path)?.users().is_empty() {
+        return Ok(None);
+    }
+
+    let backup = backup::backup_path(path, SystemTime::now());
+    fs::copy(path, &backup)?;
+
+    Ok(Some(backup))
+}
+
+/// Repairs the data file by saving the [`User`]s salvaged by the `diagnosis`
+/// of its contents, after moving the damaged file to a timestamped backup.
+///
+/// The backup is named as for [`reset`]. Returns the path of the backup.
+///
+/// # Errors
+/// This function may return an error if moving the file or saving the
+/// salvaged [`User`]s fails.
+///
+/// # Examples
+/// ```rust
+/// # use std::fs;
+/// # use user_registry_lib::{Diagnosis, command::repair};
+/// fn repair_data() {
+///     let path = "users.json";
+///     let diagnosis = Diagnosis::check(&fs::read_to_string(path).unwrap());
+///
+///     if diagnosis.is_repairable() {
+///         let backup = repair(path, &diagnosis).unwrap();
+///         println!("The damaged file was kept as {}.", backup.display());
+///     }
+/// }
+/// ```
+/// [`User`]: crate::User
+pub fn repair<P: AsRef<Path>>(path: P, diagnosis: &Diagnosis) -> Result<PathBuf, io::Error> {
+    let path = path.as_ref();
+    let backup = backup::backup_path(path, SystemTime::now());
+
+    fs::rename(path, &backup)?;
+    save_data(path, &diagnosis.data)?;
+
+    Ok(backup)
 }
@@ -0,0 +1,108 @@
+use std::{
+    ffi::OsString,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Formats a number of seconds since the Unix epoch as a compact UTC
+/// timestamp, such as `20240131T235959Z`.
+fn format_timestamp(seconds: u64) -> String {
+    let days = seconds / 86_400;
+    let time = seconds % 86_400;
+
+    // Converts days since the epoch to a civil date, following Howard
+    // Hinnant's `civil_from_days` algorithm.
+    let days = days + 719_468;
+    let era = days / 146_097;
+    let day_of_era = days % 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + u64::from(month <= 2);
+
+    format!(
+        "{year:04}{month:02}{day:02}T{:02}{:02}{:02}Z",
+        time / 3600,
+        time / 60 % 60,
+        time % 60
+    )
+}
+
+/// Returns a path next to `path` to back it up to, named after the file and
+/// the time `now`, such as `users.json.20240131T235959Z.bak`.
+///
+/// If that path is taken, a counter is added to the name until a free one is
+/// found.
+pub fn backup_path<P: AsRef<Path>>(path: P, now: SystemTime) -> PathBuf {
+    let path = path.as_ref();
+    let seconds = now
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs());
+    let timestamp = format_timestamp(seconds);
+    let file_name = path.file_name().unwrap_or_default();
+
+    let mut counter = 0;
+    loop {
+        let mut name = OsString::from(file_name);
+        name.push(".");
+        name.push(&timestamp);
+        if counter > 0 {
+            name.push(format!("-{counter}"));
+        }
+        name.push(".bak");
+
+        let backup = path.with_file_name(name);
+        if !backup.exists() {
+            return backup;
+        }
+
+        counter += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, time::Duration};
+
+    use super::*;
+
+    /// Tests formatting times as the timestamps of backup names.
+    ///
+    /// This test ensures that:
+    /// - The epoch, leap days and the end of a year are formatted correctly.
+    #[test]
+    fn format_timestamps() {
+        assert_eq!(format_timestamp(0), "19700101T000000Z");
+        assert_eq!(format_timestamp(951_782_400), "20000229T000000Z");
+        assert_eq!(format_timestamp(1_706_745_599), "20240131T235959Z");
+        assert_eq!(format_timestamp(1_735_689_599), "20241231T235959Z");
+    }
+
+    /// Tests finding the path of a backup with [`backup_path`].
+    ///
+    /// This test ensures that:
+    /// - The backup is named after the file and the time, in the same folder.
+    /// - A counter is added when the backup path is taken.
+    #[test]
+    fn find_free_backup_path() {
+        let folder = env::temp_dir().join("users_registry_backup_path_test");
+        fs::create_dir_all(&folder).unwrap();
+        let now = UNIX_EPOCH + Duration::from_secs(1_706_745_599);
+
+        let first = backup_path(folder.join("users.json"), now);
+        assert_eq!(first, folder.join("users.json.20240131T235959Z.bak"));
+
+        fs::write(&first, "").unwrap();
+        let second = backup_path(folder.join("users.json"), now);
+        fs::remove_file(&first).unwrap();
+
+        assert_eq!(second, folder.join("users.json.20240131T235959Z-1.bak"));
+    }
+}
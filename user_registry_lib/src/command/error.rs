@@ -1,9 +1,12 @@
 use std::{fmt::Display, io};
 
+use crate::PatchError;
+
 #[derive(Debug)]
 pub enum Error {
     UserNotFound(usize),
     IoError(io::Error),
+    PatchError(PatchError),
 }
 
 impl From<io::Error> for Error {
@@ -12,11 +15,18 @@ impl From<io::Error> for Error {
     }
 }
 
+impl From<PatchError> for Error {
+    fn from(err: PatchError) -> Self {
+        Self::PatchError(err)
+    }
+}
+
 impl Display for Error {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::UserNotFound(id) => write!(f, "The user with the ID {id} was not found."),
             Self::IoError(err) => write!(f, "I/O error: {err}"),
+            Self::PatchError(err) => write!(f, "Patch error: {err}"),
         }
     }
 }
@@ -0,0 +1,334 @@
+use std::{
+    io::{self, Write},
+    path::Path,
+};
+
+use crate::{Data, User};
+
+use super::data::read_data;
+
+/// The base DN used when none is provided.
+pub const DEFAULT_BASE_DN: &str = "ou=people,dc=example,dc=com";
+
+/// The maximum length of an LDIF line before it is folded.
+const LINE_WIDTH: usize = 76;
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Encodes the given bytes using standard, padded base64.
+fn base64(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+
+    for chunk in bytes.chunks(3) {
+        let b = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ];
+        let indices = [
+            b[0] >> 2,
+            (b[0] & 0b11) << 4 | b[1] >> 4,
+            (b[1] & 0b1111) << 2 | b[2] >> 6,
+            b[2] & 0b11_1111,
+        ];
+
+        for (i, index) in indices.into_iter().enumerate() {
+            if i <= chunk.len() {
+                encoded.push(BASE64_ALPHABET[index as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+
+    encoded
+}
+
+/// Returns whether a value can be written as-is after `attribute: `.
+///
+/// This follows the `SAFE-STRING` rule of RFC 2849: the value must be ASCII,
+/// contain no NUL, CR or LF, must not start with a space, colon or `<`, and
+/// must not end with a space.
+fn is_safe_string(value: &str) -> bool {
+    let starts_safe = !value.starts_with([' ', ':', '<']);
+    let ends_safe = !value.ends_with(' ');
+    let chars_safe = value
+        .bytes()
+        .all(|b| b.is_ascii() && !matches!(b, b'\0' | b'\n' | b'\r'));
+
+    starts_safe && ends_safe && chars_safe
+}
+
+/// Writes a single `attribute: value` line, base64-encoding the value when
+/// required and folding lines longer than [`LINE_WIDTH`].
+fn write_attribute<W: Write>(attribute: &str, value: &str, writer: &mut W) -> io::Result<()> {
+    let line = if is_safe_string(value) {
+        format!("{attribute}: {value}")
+    } else {
+        format!("{attribute}:: {}", base64(value.as_bytes()))
+    };
+
+    // The line is always ASCII at this point, so it can be split at any byte.
+    let (first, mut rest) = line.split_at(line.len().min(LINE_WIDTH));
+    writeln!(writer, "{first}")?;
+
+    while !rest.is_empty() {
+        let (chunk, remaining) = rest.split_at(rest.len().min(LINE_WIDTH - 1));
+        writeln!(writer, " {chunk}")?;
+        rest = remaining;
+    }
+
+    Ok(())
+}
+
+/// Writes a [`User`] as an LDIF `inetOrgPerson` entry.
+///
+/// The entry's DN is `uid=<id>,<base_dn>`, and the [`User`]'s fields are
+/// mapped to the `cn`, `sn`, `givenName`, `mail` and `telephoneNumber`
+/// attributes. `cn` and `sn` are required by `inetOrgPerson`, so they fall
+/// back to the ID when the name is empty; other attributes with an empty
+/// value are omitted. Values that are not plain ASCII are base64-encoded as
+/// required by RFC 2849.
+///
+/// # Errors
+/// This function can return an error if writing to the `writer` fails.
+///
+/// # Examples
+/// ```rust
+/// # use user_registry_lib::{User, command::ldif::write_ldif_entry};
+/// let user = User {
+///     first_name: "John".to_string(),
+///     last_name: "Doe".to_string(),
+///     email: "john@example.com".to_string(),
+///     phone_number: "555-1234".to_string(),
+/// };
+///
+/// let mut writer = Vec::new();
+/// write_ldif_entry(&user, 7, "ou=people,dc=example,dc=com", &mut writer).unwrap();
+/// ```
+pub fn write_ldif_entry<W: Write>(
+    user: &User,
+    id: usize,
+    base_dn: &str,
+    writer: &mut W,
+) -> Result<(), io::Error> {
+    let uid = id.to_string();
+    let or_uid = |value: &str| match value.trim() {
+        "" => uid.clone(),
+        _ => value.to_string(),
+    };
+    let common_name = format!("{} {}", user.first_name, user.last_name);
+
+    write_attribute("dn", &format!("uid={uid},{base_dn}"), writer)?;
+
+    for object_class in ["top", "person", "organizationalPerson", "inetOrgPerson"] {
+        write_attribute("objectClass", object_class, writer)?;
+    }
+
+    let attributes = [
+        ("uid", uid.clone()),
+        ("cn", or_uid(common_name.trim())),
+        ("sn", or_uid(&user.last_name)),
+        ("givenName", user.first_name.clone()),
+        ("mail", user.email.clone()),
+        ("telephoneNumber", user.phone_number.clone()),
+    ];
+
+    for (attribute, value) in attributes {
+        if !value.is_empty() {
+            write_attribute(attribute, &value, writer)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Writes every [`User`] in a [`Data`] as an LDIF document.
+///
+/// The document starts with the `version: 1` line, followed by one entry per
+/// [`User`] in ascending ID order, as written by [`write_ldif_entry`]. Entries
+/// are separated by a blank line.
+///
+/// # Errors
+/// This function can return an error if writing to the `writer` fails.
+pub fn write_ldif<W: Write>(data: &Data, base_dn: &str, writer: &mut W) -> Result<(), io::Error> {
+    let mut users = data.users();
+    users.sort_by_key(|(id, _)| *id);
+
+    writeln!(writer, "version: 1")?;
+
+    for (id, user) in users {
+        writeln!(writer)?;
+        write_ldif_entry(user, id, base_dn, writer)?;
+    }
+
+    Ok(())
+}
+
+/// Reads user data from a file at the specified `path` and writes it to the
+/// provided writer as an LDIF document.
+///
+/// See [`write_ldif`] for details about the output.
+///
+/// # Errors
+/// This function can return an error if reading from the file or writing to the
+/// `writer` fails.
+///
+/// # Examples
+/// ```rust
+/// # use std::io::stdout;
+/// # use user_registry_lib::command::ldif::{export_ldif, DEFAULT_BASE_DN};
+/// fn export() {
+///     export_ldif("path/to/data.json", DEFAULT_BASE_DN, &mut stdout()).unwrap();
+/// }
+/// ```
+pub fn export_ldif<P: AsRef<Path>, W: Write>(
+    path: P,
+    base_dn: &str,
+    writer: &mut W,
+) -> Result<(), io::Error> {
+    let data = read_data(&path)?;
+    write_ldif(&data, base_dn, writer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Helper function to create a [`User`] with data for testing purposes.
+    fn first_user() -> User {
+        User {
+            first_name: String::from("firstName"),
+            last_name: String::from("firstSurname"),
+            email: String::from("firstEmail"),
+            phone_number: String::from("0123456789"),
+        }
+    }
+
+    /// Helper function to create a [`User`] with non-ASCII data for testing
+    /// purposes.
+    fn second_user() -> User {
+        User {
+            first_name: String::from("José"),
+            last_name: String::from("Muñoz"),
+            email: String::from(" secondEmail"),
+            phone_number: String::new(),
+        }
+    }
+
+    /// Tests the base64 encoder against the RFC 4648 test vectors.
+    #[test]
+    fn base64_test_vectors() {
+        let vectors = [
+            ("", ""),
+            ("f", "Zg=="),
+            ("fo", "Zm8="),
+            ("foo", "Zm9v"),
+            ("foob", "Zm9vYg=="),
+            ("fooba", "Zm9vYmE="),
+            ("foobar", "Zm9vYmFy"),
+        ];
+
+        for (input, output) in vectors {
+            assert_eq!(base64(input.as_bytes()), output);
+        }
+    }
+
+    /// Tests writing a [`Data`] as an LDIF document.
+    ///
+    /// This test ensures that:
+    /// - The document starts with the version line and separates entries.
+    /// - ASCII values are written as-is.
+    /// - Non-ASCII values and values with a leading space are base64-encoded.
+    /// - Empty values are omitted.
+    #[test]
+    fn write_ldif_and_check_buffer() {
+        let mut data = Data::new();
+        data.add_user(first_user());
+        data.add_user(second_user());
+
+        let mut writer = Vec::new();
+        write_ldif(&data, "dc=example,dc=com", &mut writer).unwrap();
+
+        assert_eq!(
+            String::from_utf8(writer).unwrap(),
+            "version: 1
+
+dn: uid=0,dc=example,dc=com
+objectClass: top
+objectClass: person
+objectClass: organizationalPerson
+objectClass: inetOrgPerson
+uid: 0
+cn: firstName firstSurname
+sn: firstSurname
+givenName: firstName
+mail: firstEmail
+telephoneNumber: 0123456789
+
+dn: uid=1,dc=example,dc=com
+objectClass: top
+objectClass: person
+objectClass: organizationalPerson
+objectClass: inetOrgPerson
+uid: 1
+cn:: Sm9zw6kgTXXDsW96
+sn:: TXXDsW96
+givenName:: Sm9zw6k=
+mail:: IHNlY29uZEVtYWls
+"
+        );
+    }
+
+    /// Tests writing the entry of a [`User`] without a name.
+    ///
+    /// This test ensures that:
+    /// - The required `cn` and `sn` attributes fall back to the ID.
+    /// - The optional `givenName` attribute is omitted.
+    #[test]
+    fn write_required_attributes_without_name() {
+        let user = User {
+            first_name: String::new(),
+            last_name: String::from(" "),
+            email: String::from("firstEmail"),
+            phone_number: String::new(),
+        };
+
+        let mut writer = Vec::new();
+        write_ldif_entry(&user, 4, "dc=example,dc=com", &mut writer).unwrap();
+
+        assert_eq!(
+            String::from_utf8(writer).unwrap(),
+            "dn: uid=4,dc=example,dc=com
+objectClass: top
+objectClass: person
+objectClass: organizationalPerson
+objectClass: inetOrgPerson
+uid: 4
+cn: 4
+sn: 4
+mail: firstEmail
+"
+        );
+    }
+
+    /// Tests that long lines are folded.
+    #[test]
+    fn fold_long_lines() {
+        let value = "a".repeat(100);
+        let mut writer = Vec::new();
+        write_attribute("cn", &value, &mut writer).unwrap();
+
+        let output = String::from_utf8(writer).unwrap();
+        let lines: Vec<_> = output.lines().collect();
+
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].len(), LINE_WIDTH);
+        assert!(lines[1].starts_with(' '));
+        assert_eq!(
+            format!("{}{}", lines[0], &lines[1][1..]),
+            format!("cn: {value}")
+        );
+    }
+}
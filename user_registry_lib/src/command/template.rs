@@ -0,0 +1,388 @@
+use std::{
+    borrow::Cow,
+    error,
+    fmt::{self, Display, Formatter},
+    io::{self, Write},
+};
+
+use crate::{Field, User};
+
+/// The placeholder names that can be used inside a [`Template`]: `id`, then
+/// the name of every [`Field`].
+pub const PLACEHOLDERS: [&str; Field::ALL.len() + 1] = {
+    let mut names = [ID_PLACEHOLDER; Field::ALL.len() + 1];
+    let mut index = 0;
+
+    while index < Field::ALL.len() {
+        names[index + 1] = Field::ALL[index].name();
+        index += 1;
+    }
+
+    names
+};
+
+/// The name of the placeholder of a [`User`]'s ID.
+const ID_PLACEHOLDER: &str = "id";
+
+/// The filter names that can be applied to a placeholder inside a
+/// [`Template`].
+pub const FILTERS: [&str; 2] = ["csv", "shell"];
+
+/// A value that can be referenced by a [`Template`] placeholder: the ID of a
+/// [`User`] or one of its [`Field`]s.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Placeholder {
+    Id,
+    Field(Field),
+}
+
+impl Placeholder {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            ID_PLACEHOLDER => Some(Self::Id),
+            name => Field::from_name(name).map(Self::Field),
+        }
+    }
+
+    fn value<'a>(&self, user: &'a User, id: usize) -> Cow<'a, str> {
+        match self {
+            Self::Id => Cow::Owned(id.to_string()),
+            Self::Field(field) => Cow::Borrowed(field.value(user)),
+        }
+    }
+}
+
+/// An escaping strategy applied to a placeholder's value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Filter {
+    Csv,
+    Shell,
+}
+
+impl Filter {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "csv" => Some(Self::Csv),
+            "shell" => Some(Self::Shell),
+            _ => None,
+        }
+    }
+
+    fn apply<'a>(&self, value: Cow<'a, str>) -> Cow<'a, str> {
+        match self {
+            Self::Csv => Cow::Owned(escape_csv(&value).into_owned()),
+            Self::Shell => Cow::Owned(escape_shell(&value).into_owned()),
+        }
+    }
+}
+
+/// A single piece of a parsed [`Template`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Literal(String),
+    Placeholder(Placeholder, Option<Filter>),
+}
+
+/// An error produced while parsing a [`Template`].
+///
+/// Every variant carries the byte offset in the template string where the
+/// problem was found.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TemplateError {
+    UnknownPlaceholder { name: String, position: usize },
+    UnknownFilter { name: String, position: usize },
+    UnclosedPlaceholder(usize),
+    UnmatchedBrace(usize),
+}
+
+impl Display for TemplateError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownPlaceholder { name, position } => write!(
+                f,
+                "Unknown placeholder '{{{name}}}' at position {position}. Valid placeholders are: {}.",
+                PLACEHOLDERS.join(", ")
+            ),
+            Self::UnknownFilter { name, position } => write!(
+                f,
+                "Unknown filter '{name}' at position {position}. Valid filters are: {}.",
+                FILTERS.join(", ")
+            ),
+            Self::UnclosedPlaceholder(position) => {
+                write!(f, "The placeholder at position {position} is never closed.")
+            }
+            Self::UnmatchedBrace(position) => write!(
+                f,
+                "Unmatched '}}' at position {position}. Use '}}}}' to write a literal brace."
+            ),
+        }
+    }
+}
+
+impl error::Error for TemplateError {}
+
+/// A user-defined format used to write a [`User`] on a single line.
+///
+/// Templates are plain strings where placeholders between braces are replaced
+/// with the [`User`]'s fields, such as `{last_name}, {first_name} <{email}>`.
+/// The available placeholders are listed in [`PLACEHOLDERS`]. A placeholder
+/// can be followed by a filter to escape its value, as in `{email:csv}`; the
+/// available filters are listed in [`FILTERS`]. Literal braces are written as
+/// `{{` and `}}`.
+///
+/// # Examples
+/// ```rust
+/// # use user_registry_lib::{User, command::template::Template};
+/// let user = User {
+///     first_name: "John".to_string(),
+///     last_name: "Doe".to_string(),
+///     email: "john@example.com".to_string(),
+///     phone_number: "555-1234".to_string(),
+/// };
+///
+/// let template = Template::parse("{last_name}, {first_name} <{email}>").unwrap();
+/// assert_eq!(template.render(&user, 7), "Doe, John <john@example.com>");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Template {
+    segments: Vec<Segment>,
+}
+
+impl Template {
+    /// Parses a `Template` from its textual representation.
+    ///
+    /// # Errors
+    /// This function returns a [`TemplateError`] if the template references
+    /// an unknown placeholder or filter, or if its braces are unbalanced.
+    pub fn parse(source: &str) -> Result<Self, TemplateError> {
+        let mut segments = Vec::new();
+        let mut literal = String::new();
+        let mut chars = source.char_indices().peekable();
+
+        while let Some((position, c)) = chars.next() {
+            match c {
+                '{' if chars.next_if(|(_, c)| *c == '{').is_some() => literal.push('{'),
+                '}' if chars.next_if(|(_, c)| *c == '}').is_some() => literal.push('}'),
+                '}' => return Err(TemplateError::UnmatchedBrace(position)),
+                '{' => {
+                    let start = position + 1;
+                    let end = source[start..]
+                        .find('}')
+                        .map(|offset| start + offset)
+                        .ok_or(TemplateError::UnclosedPlaceholder(position))?;
+
+                    let (name, filter) = match source[start..end].split_once(':') {
+                        Some((name, filter)) => (name.trim(), Some(filter.trim())),
+                        None => (source[start..end].trim(), None),
+                    };
+
+                    let placeholder = Placeholder::from_name(name).ok_or_else(|| {
+                        TemplateError::UnknownPlaceholder {
+                            name: name.to_string(),
+                            position,
+                        }
+                    })?;
+
+                    let filter = filter
+                        .map(|filter| {
+                            Filter::from_name(filter).ok_or_else(|| TemplateError::UnknownFilter {
+                                name: filter.to_string(),
+                                position,
+                            })
+                        })
+                        .transpose()?;
+
+                    if !literal.is_empty() {
+                        segments.push(Segment::Literal(std::mem::take(&mut literal)));
+                    }
+                    segments.push(Segment::Placeholder(placeholder, filter));
+
+                    while chars.next_if(|(index, _)| *index <= end).is_some() {}
+                }
+                c => literal.push(c),
+            }
+        }
+
+        if !literal.is_empty() {
+            segments.push(Segment::Literal(literal));
+        }
+
+        Ok(Self { segments })
+    }
+
+    /// Renders the `Template` for the given [`User`] and `id`.
+    pub fn render(&self, user: &User, id: usize) -> String {
+        let mut output = String::new();
+
+        for segment in &self.segments {
+            match segment {
+                Segment::Literal(literal) => output.push_str(literal),
+                Segment::Placeholder(placeholder, filter) => {
+                    let value = placeholder.value(user, id);
+                    let value = match filter {
+                        Some(filter) => filter.apply(value),
+                        None => value,
+                    };
+                    output.push_str(&value);
+                }
+            }
+        }
+
+        output
+    }
+
+    /// Renders the `Template` for the given [`User`] and `id`, and writes it
+    /// to the provided writer followed by a newline.
+    ///
+    /// # Errors
+    /// This function can return an error if writing to the `writer` fails.
+    pub fn write_user<W: Write>(
+        &self,
+        user: &User,
+        id: usize,
+        writer: &mut W,
+    ) -> Result<(), io::Error> {
+        writeln!(writer, "{}", self.render(user, id))
+    }
+}
+
+/// Escapes a value so it can be used as a single CSV field.
+///
+/// Values containing commas, quotes or line breaks are wrapped in double
+/// quotes, and inner double quotes are doubled. Any other value is returned
+/// unchanged.
+///
+/// # Examples
+/// ```rust
+/// # use user_registry_lib::command::template::escape_csv;
+/// assert_eq!(escape_csv("Doe"), "Doe");
+/// assert_eq!(escape_csv("Doe, John"), "\"Doe, John\"");
+/// assert_eq!(escape_csv("\"J\""), "\"\"\"J\"\"\"");
+/// ```
+pub fn escape_csv(value: &str) -> Cow<'_, str> {
+    if value.contains([',', '"', '\n', '\r']) {
+        Cow::Owned(format!("\"{}\"", value.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(value)
+    }
+}
+
+/// Escapes a value so it can be used as a single POSIX shell word.
+///
+/// Values made only of characters that have no special meaning to the shell
+/// are returned unchanged. Any other value is wrapped in single quotes, with
+/// inner single quotes written as `'\''`.
+///
+/// # Examples
+/// ```rust
+/// # use user_registry_lib::command::template::escape_shell;
+/// assert_eq!(escape_shell("john@example.com"), "john@example.com");
+/// assert_eq!(escape_shell("John Doe"), "'John Doe'");
+/// assert_eq!(escape_shell("O'Brien"), "'O'\\''Brien'");
+/// assert_eq!(escape_shell(""), "''");
+/// ```
+pub fn escape_shell(value: &str) -> Cow<'_, str> {
+    let is_safe = |c: char| c.is_ascii_alphanumeric() || "@%+=:,./_-".contains(c);
+
+    if !value.is_empty() && value.chars().all(is_safe) {
+        Cow::Borrowed(value)
+    } else {
+        Cow::Owned(format!("'{}'", value.replace('\'', "'\\''")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Helper function to create a [`User`] with data for testing purposes.
+    fn user() -> User {
+        User {
+            first_name: String::from("John"),
+            last_name: String::from("O'Doe, Jr."),
+            email: String::from("john@example.com"),
+            phone_number: String::from("0123456789"),
+        }
+    }
+
+    /// Tests rendering a [`Template`] with every placeholder.
+    ///
+    /// This test ensures that:
+    /// - Every placeholder is replaced with the matching [`User`] field.
+    /// - Literal text and escaped braces are kept.
+    #[test]
+    fn render_all_placeholders() {
+        let template =
+            Template::parse("{{{id}}} {first_name} {last_name} <{email}> {phone_number}").unwrap();
+
+        assert_eq!(
+            template.render(&user(), 7),
+            "{7} John O'Doe, Jr. <john@example.com> 0123456789"
+        );
+    }
+
+    /// Tests rendering a [`Template`] with filters.
+    ///
+    /// This test ensures that:
+    /// - The `csv` filter quotes values containing commas.
+    /// - The `shell` filter quotes values containing single quotes.
+    #[test]
+    fn render_filters() {
+        let template = Template::parse("{last_name:csv},{email:csv}").unwrap();
+        assert_eq!(
+            template.render(&user(), 0),
+            "\"O'Doe, Jr.\",john@example.com"
+        );
+
+        let template = Template::parse("echo {last_name:shell}").unwrap();
+        assert_eq!(template.render(&user(), 0), "echo 'O'\\''Doe, Jr.'");
+    }
+
+    /// Tests listing the placeholders of a [`Template`].
+    ///
+    /// This test ensures that:
+    /// - The ID and every [`Field`] have a placeholder, named as in JSON.
+    /// - Every listed placeholder can be parsed.
+    #[test]
+    fn list_every_placeholder() {
+        assert_eq!(
+            PLACEHOLDERS,
+            ["id", "first_name", "last_name", "email", "phone_number"]
+        );
+
+        for name in PLACEHOLDERS {
+            assert!(Template::parse(&format!("{{{name}}}")).is_ok());
+        }
+    }
+
+    /// Tests parsing invalid [`Template`]s.
+    ///
+    /// This test ensures that:
+    /// - Unknown placeholders and filters are reported with their position.
+    /// - Unbalanced braces are reported.
+    #[test]
+    fn parse_errors() {
+        assert_eq!(
+            Template::parse("Name: {name}"),
+            Err(TemplateError::UnknownPlaceholder {
+                name: String::from("name"),
+                position: 6
+            })
+        );
+        assert_eq!(
+            Template::parse("{email:xml}"),
+            Err(TemplateError::UnknownFilter {
+                name: String::from("xml"),
+                position: 0
+            })
+        );
+        assert_eq!(
+            Template::parse("{email"),
+            Err(TemplateError::UnclosedPlaceholder(0))
+        );
+        assert_eq!(
+            Template::parse("email}"),
+            Err(TemplateError::UnmatchedBrace(5))
+        );
+    }
+}
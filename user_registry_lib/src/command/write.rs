@@ -3,9 +3,9 @@ use std::{
     path::Path,
 };
 
-use crate::{Data, User};
+use crate::{Data, Diff, User};
 
-use super::data::read_data;
+use super::{data::read_data, template::Template};
 
 /// Writes a [`User`]'s details to the provided writer.
 ///
@@ -52,7 +52,7 @@ pub fn write_user<W: Write>(user: &User, id: usize, writer: &mut W) -> Result<()
 /// This function can return an error if writing to the `writer` fails.
 ///
 /// # Examples
-/// ```ignore
+/// ```rust
 /// # use user_registry_lib::{command::show_data, Data, User};
 /// let mut data = Data::new();
 ///
@@ -66,7 +66,7 @@ pub fn write_user<W: Write>(user: &User, id: usize, writer: &mut W) -> Result<()
 /// let mut writer = Vec::new();
 /// show_data(&data, &mut writer).unwrap();
 /// ```
-fn show_data<W: Write>(data: &Data, writer: &mut W) -> Result<(), io::Error> {
+pub fn show_data<W: Write>(data: &Data, writer: &mut W) -> Result<(), io::Error> {
     let mut users = data.users().to_owned();
     users.sort_by_key(|(id, _)| *id);
 
@@ -85,6 +85,38 @@ fn show_data<W: Write>(data: &Data, writer: &mut W) -> Result<(), io::Error> {
     Ok(())
 }
 
+/// Writes every [`User`] of a [`Data`] to the provided writer using a
+/// [`Template`].
+///
+/// Each [`User`] is rendered on its own line with the given [`Template`], in
+/// ascending ID order.
+///
+/// # Errors
+/// This function can return an error if writing to the `writer` fails.
+///
+/// # Examples
+/// ```rust
+/// # use user_registry_lib::{command::{show_data_with_template, Template}, Data};
+/// let template = Template::parse("{email}").unwrap();
+///
+/// let mut writer = Vec::new();
+/// show_data_with_template(&Data::new(), &template, &mut writer).unwrap();
+/// ```
+pub fn show_data_with_template<W: Write>(
+    data: &Data,
+    template: &Template,
+    writer: &mut W,
+) -> Result<(), io::Error> {
+    let mut users = data.users();
+    users.sort_by_key(|(id, _)| *id);
+
+    for (id, user) in users {
+        template.write_user(user, id, writer)?;
+    }
+
+    Ok(())
+}
+
 /// Reads user data from a file at the specified `path` and writes it to the
 /// provided writer.
 ///
@@ -115,6 +147,90 @@ pub fn show<P: AsRef<Path>, W: Write>(path: P, writer: &mut W) -> Result<(), io:
     show_data(&data, writer)
 }
 
+/// Reads user data from a file at the specified `path` and writes every
+/// [`User`] to the provided writer using a [`Template`].
+///
+/// This function behaves like [`show`], except that each [`User`] is rendered
+/// on its own line with the given [`Template`] instead of the default
+/// multi-line format. The [`User`]s are written in ascending ID order.
+///
+/// # Errors
+/// This function can return an error if reading from the file or writing to the
+/// `writer` fails.
+///
+/// # Examples
+/// ```rust
+/// # use std::io::stdout;
+/// # use user_registry_lib::command::{show_with_template, template::Template};
+/// fn show_emails() {
+///     let template = Template::parse("{email}").unwrap();
+///     show_with_template("path/to/data.json", &template, &mut stdout()).unwrap();
+/// }
+/// ```
+pub fn show_with_template<P: AsRef<Path>, W: Write>(
+    path: P,
+    template: &Template,
+    writer: &mut W,
+) -> Result<(), io::Error> {
+    let data = read_data(&path)?;
+    show_data_with_template(&data, template, writer)
+}
+
+/// Writes a human-readable summary of a [`Diff`] to the provided writer.
+///
+/// Each added or removed [`User`] is written on its own line with their ID and
+/// name, and each modified [`User`] is followed by one indented line per
+/// changed field showing the old and new values. If the [`Diff`] is empty, a
+/// single line saying so is written instead.
+///
+/// # Errors
+/// This function can return an error if writing to the `writer` fails.
+///
+/// # Examples
+/// ```rust
+/// # use user_registry_lib::{Data, Diff, command::write_diff};
+/// let diff = Diff::new(&Data::new(), &Data::new());
+///
+/// let mut writer = Vec::new();
+/// write_diff(&diff, &mut writer).unwrap();
+/// assert_eq!(writer, b"No differences.\n");
+/// ```
+pub fn write_diff<W: Write>(diff: &Diff, writer: &mut W) -> Result<(), io::Error> {
+    if diff.is_empty() {
+        return writeln!(writer, "No differences.");
+    }
+
+    for (id, user) in &diff.added {
+        writeln!(
+            writer,
+            "+ User {id}: {} {}",
+            user.first_name, user.last_name
+        )?;
+    }
+
+    for (id, user) in &diff.removed {
+        writeln!(
+            writer,
+            "- User {id}: {} {}",
+            user.first_name, user.last_name
+        )?;
+    }
+
+    for modification in &diff.modified {
+        writeln!(writer, "~ User {}:", modification.id)?;
+
+        for change in &modification.changes {
+            writeln!(
+                writer,
+                "    {}: {:?} -> {:?}",
+                change.field, change.old, change.new
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -192,4 +308,37 @@ User 1:
     Phone number: 9786543210\n"
         );
     }
+
+    /// Tests the behavior of writing a [`Diff`] using the [`write_diff`]
+    /// function.
+    ///
+    /// This test ensures that:
+    /// - Added and removed [`User`]s are written with their ID and name.
+    /// - Modified [`User`]s list every changed field.
+    #[test]
+    fn write_diff_and_check_buffer() {
+        let mut old = Data::new();
+        old.add_user(first_user());
+        old.add_user(second_user());
+
+        let mut changed = first_user();
+        changed.email = String::from("newEmail");
+
+        let mut new = Data::new();
+        new.add_user(changed);
+        new.add_user(second_user());
+        new.add_user(second_user());
+        new.remove_user(1);
+
+        let mut writer = Vec::new();
+        write_diff(&Diff::new(&old, &new), &mut writer).unwrap();
+
+        assert_eq!(
+            writer,
+            b"+ User 2: secondName secondSurname
+- User 1: secondName secondSurname
+~ User 0:
+    Email: \"firstEmail\" -> \"newEmail\"\n"
+        );
+    }
 }
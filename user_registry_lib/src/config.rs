@@ -0,0 +1,955 @@
+use std::{
+    collections::BTreeMap,
+    env, error,
+    fmt::{self, Display, Formatter},
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use clap::ValueEnum;
+use toml_edit::{DocumentMut, Item, Table, TableLike, TomlError, value};
+
+use crate::ConflictPolicy;
+
+/// The environment variable holding the path of the configuration file.
+pub const CONFIG_VAR: &str = "USER_REGISTRY_CONFIG";
+
+/// The environment variable holding the name of the selected profile.
+pub const PROFILE_VAR: &str = "USER_REGISTRY_PROFILE";
+
+/// The environment variable holding the path of the data file.
+pub const DATA_VAR: &str = "USER_REGISTRY_DATA";
+
+/// The environment variable holding the default [`OutputFormat`].
+pub const FORMAT_VAR: &str = "USER_REGISTRY_FORMAT";
+
+/// The environment variable holding the default [`ConflictPolicy`].
+pub const POLICY_VAR: &str = "USER_REGISTRY_POLICY";
+
+/// The environment variable holding the default LDIF base DN.
+pub const BASE_DN_VAR: &str = "USER_REGISTRY_BASE_DN";
+
+/// Formats supported by commands that print structured results.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Human-readable text.
+    Text,
+
+    /// JSON, for use by other programs.
+    Json,
+}
+
+/// An error produced while loading a [`Config`].
+#[derive(Debug)]
+pub enum ConfigError {
+    /// The configuration file couldn't be read.
+    IoError(io::Error),
+
+    /// The configuration file is not valid TOML.
+    Syntax(TomlError),
+
+    /// A key of the configuration file is not supported.
+    UnknownKey(String),
+
+    /// A setting has a value of the wrong type or an unsupported value. The
+    /// setting is named by its key or environment variable.
+    InvalidValue { setting: String, value: String },
+
+    /// No profile has the given name.
+    UnknownProfile(String),
+
+    /// A profile with the given name already exists.
+    ProfileExists(String),
+}
+
+impl Display for ConfigError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::IoError(err) => write!(f, "I/O error: {err}"),
+            Self::Syntax(err) => write!(f, "Invalid configuration file: {err}"),
+            Self::UnknownKey(key) => write!(f, "Unknown configuration key '{key}'."),
+            Self::InvalidValue { setting, value } => {
+                write!(f, "Invalid value '{value}' for {setting}.")
+            }
+            Self::UnknownProfile(name) => write!(f, "The profile '{name}' does not exist."),
+            Self::ProfileExists(name) => write!(f, "The profile '{name}' already exists."),
+        }
+    }
+}
+
+impl error::Error for ConfigError {}
+
+impl From<io::Error> for ConfigError {
+    fn from(err: io::Error) -> Self {
+        Self::IoError(err)
+    }
+}
+
+impl From<TomlError> for ConfigError {
+    fn from(err: TomlError) -> Self {
+        Self::Syntax(err)
+    }
+}
+
+/// An error produced while finding the data file with
+/// [`Config::find_data_file`] or [`Config::prepare_data_file`].
+#[derive(Debug)]
+pub enum DataFileError {
+    /// No data file was given, and there is no data directory to default to.
+    NoPath,
+
+    /// The data file path has no parent folder.
+    NoParent,
+
+    /// The folder of the data file couldn't be created.
+    CreateFolder(io::Error),
+
+    /// The data file path exists but is not a file.
+    NotAFile,
+}
+
+impl Display for DataFileError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoPath => write!(
+                f,
+                "Couldn't get data file path. Try using --data to specify one."
+            ),
+            Self::NoParent => write!(f, "Couldn't get data file parent."),
+            Self::CreateFolder(err) => write!(f, "Failed to create data directory: {err}"),
+            Self::NotAFile => write!(f, "The data path must be a file. Specify another one."),
+        }
+    }
+}
+
+impl error::Error for DataFileError {}
+
+/// Settings shared by every front end of the registry.
+///
+/// Each setting is taken from the first source that defines it, in this order:
+///
+/// 1. the command line, applied by the front end;
+/// 2. the environment variables, e.g. [`DATA_VAR`];
+/// 3. the selected profile of the configuration file;
+/// 4. the top level of the configuration file, at [`CONFIG_VAR`] or
+///    `$XDG_CONFIG_HOME/users_registry/config.toml`;
+/// 5. the built-in defaults.
+///
+/// The profile is selected the same way, from the command line,
+/// [`PROFILE_VAR`] or the `profile` key of the configuration file. A profile
+/// selected on the command line comes before the environment variables, so
+/// that [`DATA_VAR`] doesn't replace its data file. See
+/// [`ConfigFile`] for the format of the file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Config {
+    /// The name of the selected profile.
+    pub profile: Option<String>,
+
+    /// The data file to load and save [`User`]s.
+    ///
+    /// [`User`]: crate::User
+    pub data: Option<PathBuf>,
+
+    /// The format of structured results.
+    pub format: Option<OutputFormat>,
+
+    /// How to handle conflicting [`User`]s when merging.
+    ///
+    /// [`User`]: crate::User
+    pub policy: Option<ConflictPolicy>,
+
+    /// The base DN under which LDIF entries are created.
+    pub base_dn: Option<String>,
+}
+
+/// Parses the value of a [`ValueEnum`] setting.
+fn parse_enum<T: ValueEnum>(setting: &str, value: &str) -> Result<T, ConfigError> {
+    T::from_str(value, true).map_err(|_| ConfigError::InvalidValue {
+        setting: setting.to_string(),
+        value: value.to_string(),
+    })
+}
+
+/// Returns the name of a [`ValueEnum`] value, as written in settings.
+fn enum_name<T: ValueEnum>(value: &T) -> String {
+    value
+        .to_possible_value()
+        .map(|value| value.get_name().to_string())
+        .unwrap_or_default()
+}
+
+/// Reads the settings of a table of the configuration file. The keys of the
+/// table are prefixed with `prefix` in errors.
+///
+/// Keys that aren't settings are passed to `other`, which returns whether
+/// they are allowed.
+fn parse_settings(
+    table: &dyn TableLike,
+    prefix: &str,
+    mut other: impl FnMut(&str, &Item) -> Result<bool, ConfigError>,
+) -> Result<Config, ConfigError> {
+    let mut config = Config::default();
+
+    for (key, item) in table.iter() {
+        let setting = format!("{prefix}{key}");
+        let invalid = || ConfigError::InvalidValue {
+            setting: setting.clone(),
+            value: item.to_string().trim().to_string(),
+        };
+        let value = || item.as_str().ok_or_else(invalid);
+
+        match key {
+            "data" => config.data = Some(PathBuf::from(value()?)),
+            "format" => config.format = Some(parse_enum(&setting, value()?)?),
+            "policy" => config.policy = Some(parse_enum(&setting, value()?)?),
+            "base_dn" => config.base_dn = Some(value()?.to_string()),
+            _ if other(key, item)? => {}
+            _ => return Err(ConfigError::UnknownKey(setting)),
+        }
+    }
+
+    Ok(config)
+}
+
+impl Config {
+    /// Returns the path of the configuration file: the one in
+    /// [`CONFIG_VAR`], or `config.toml` in the `users_registry` folder of the
+    /// config directory.
+    pub fn path() -> Option<PathBuf> {
+        env::var_os(CONFIG_VAR)
+            .filter(|path| !path.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| {
+                dirs::config_dir().map(|mut path| {
+                    path.push("users_registry");
+                    path.push("config.toml");
+                    path
+                })
+            })
+    }
+
+    /// Reads the settings from the environment variables, looked up with
+    /// `var`. Empty variables are ignored.
+    fn from_vars<F: Fn(&str) -> Option<String>>(var: F) -> Result<Self, ConfigError> {
+        let var = |name| var(name).filter(|value| !value.is_empty());
+
+        Ok(Self {
+            profile: var(PROFILE_VAR),
+            data: var(DATA_VAR).map(PathBuf::from),
+            format: var(FORMAT_VAR)
+                .map(|value| parse_enum(FORMAT_VAR, &value))
+                .transpose()?,
+            policy: var(POLICY_VAR)
+                .map(|value| parse_enum(POLICY_VAR, &value))
+                .transpose()?,
+            base_dn: var(BASE_DN_VAR),
+        })
+    }
+
+    /// Reads the settings from the environment variables.
+    ///
+    /// # Errors
+    /// This function may return an error if a variable has an invalid value.
+    pub fn from_env() -> Result<Self, ConfigError> {
+        Self::from_vars(|name| env::var(name).ok())
+    }
+
+    /// Returns these settings, with the unset ones taken from `fallback`.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use std::path::PathBuf;
+    ///
+    /// use user_registry_lib::Config;
+    ///
+    /// let env = Config { data: Some(PathBuf::from("env.json")), ..Config::default() };
+    /// let file = Config {
+    ///     data: Some(PathBuf::from("file.json")),
+    ///     base_dn: Some(String::from("dc=example")),
+    ///     ..Config::default()
+    /// };
+    ///
+    /// let config = env.or(file);
+    /// assert_eq!(config.data, Some(PathBuf::from("env.json")));
+    /// assert_eq!(config.base_dn.as_deref(), Some("dc=example"));
+    /// ```
+    pub fn or(self, fallback: Self) -> Self {
+        Self {
+            profile: self.profile.or(fallback.profile),
+            data: self.data.or(fallback.data),
+            format: self.format.or(fallback.format),
+            policy: self.policy.or(fallback.policy),
+            base_dn: self.base_dn.or(fallback.base_dn),
+        }
+    }
+
+    /// Loads the settings from the environment variables and the
+    /// configuration file, as described in [`Config`].
+    ///
+    /// `profile` is the profile selected on the command line, if any.
+    ///
+    /// # Errors
+    /// This function may return an error if a variable has an invalid value,
+    /// if the configuration file can't be read or parsed, or if the selected
+    /// profile doesn't exist.
+    pub fn load(profile: Option<&str>) -> Result<Self, ConfigError> {
+        Self::resolve(&ConfigFile::load()?, profile, Self::from_env()?)
+    }
+
+    /// Completes the settings of the environment variables, `env`, with the
+    /// ones of `file`, as described in [`Config`].
+    fn resolve(file: &ConfigFile, profile: Option<&str>, env: Self) -> Result<Self, ConfigError> {
+        let Some(name) = profile else {
+            return file.resolve(env);
+        };
+
+        let selected = file
+            .profile(name)
+            .cloned()
+            .ok_or_else(|| ConfigError::UnknownProfile(name.to_string()))?;
+
+        Ok(Self {
+            profile: Some(name.to_string()),
+            ..selected
+        }
+        .or(env)
+        .or(file.settings().clone()))
+    }
+
+    /// Returns the configured data file, or `users.json` in the
+    /// `users_registry` folder of the data directory.
+    pub fn data_file(&self) -> Option<PathBuf> {
+        self.data.clone().or_else(|| {
+            dirs::data_dir().map(|mut path| {
+                path.push("users_registry");
+                path.push("users.json");
+                path
+            })
+        })
+    }
+
+    /// Returns the data file to use, `data` if given or else the configured
+    /// one, without changing anything on disk.
+    ///
+    /// This is how every front end finds its data file, and how read-only
+    /// ones use it.
+    ///
+    /// # Errors
+    /// This function may return an error if there is no data file path, or if
+    /// the path exists but is not a file.
+    pub fn find_data_file(&self, data: Option<PathBuf>) -> Result<PathBuf, DataFileError> {
+        let data_file = data
+            .or_else(|| self.data_file())
+            .ok_or(DataFileError::NoPath)?;
+
+        if data_file.exists() && !data_file.is_file() {
+            return Err(DataFileError::NotAFile);
+        }
+
+        Ok(data_file)
+    }
+
+    /// Returns the data file to use, as found by [`Config::find_data_file`],
+    /// after creating its folder if needed so that it can be saved.
+    ///
+    /// # Errors
+    /// This function may return an error if there is no data file path, if its
+    /// folder can't be created, or if the path exists but is not a file.
+    pub fn prepare_data_file(&self, data: Option<PathBuf>) -> Result<PathBuf, DataFileError> {
+        let data_file = self.find_data_file(data)?;
+        let parent = data_file.parent().ok_or(DataFileError::NoParent)?;
+
+        fs::create_dir_all(parent).map_err(DataFileError::CreateFolder)?;
+
+        Ok(data_file)
+    }
+}
+
+/// The configuration file, which holds default settings and named profiles.
+///
+/// Top-level keys are the defaults, `profile` names the profile selected by
+/// default, and every `[profiles.<name>]` table holds the settings of a
+/// profile. Settings are `data`, `format`, `policy` and `base_dn`, e.g.:
+///
+/// ```toml
+/// profile = "staff"
+/// format = "json"
+///
+/// [profiles.staff]
+/// data = "/srv/registry/staff.json"
+/// base_dn = "ou=staff,dc=example,dc=org"
+///
+/// [profiles.contractors]
+/// data = "/srv/registry/contractors.json"
+/// policy = "match-by-email"
+/// ```
+///
+/// Changes keep the comments and layout of the rest of the file.
+#[derive(Debug, Clone, Default)]
+pub struct ConfigFile {
+    document: DocumentMut,
+    settings: Config,
+    profiles: BTreeMap<String, Config>,
+}
+
+impl ConfigFile {
+    /// Parses the contents of a configuration file.
+    ///
+    /// # Errors
+    /// This function may return an error if `source` is not valid TOML or if
+    /// it contains unknown keys or invalid values.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use user_registry_lib::{ConfigFile, OutputFormat};
+    ///
+    /// let file = ConfigFile::parse("format = \"json\"").unwrap();
+    /// assert_eq!(file.settings().format, Some(OutputFormat::Json));
+    /// assert!(ConfigFile::parse("format = \"xml\"").is_err());
+    /// ```
+    pub fn parse(source: &str) -> Result<Self, ConfigError> {
+        let document: DocumentMut = source.parse()?;
+        let mut profile = None;
+        let mut profiles = BTreeMap::new();
+
+        let mut settings = parse_settings(document.as_table(), "", |key, item| {
+            match key {
+                "profile" => {
+                    let name = item.as_str().ok_or_else(|| ConfigError::InvalidValue {
+                        setting: key.to_string(),
+                        value: item.to_string().trim().to_string(),
+                    })?;
+                    profile = Some(name.to_string());
+                }
+                "profiles" => {
+                    let table = item
+                        .as_table_like()
+                        .ok_or_else(|| ConfigError::UnknownKey(key.to_string()))?;
+
+                    for (name, item) in table.iter() {
+                        let prefix = format!("profiles.{name}.");
+                        let table = item
+                            .as_table_like()
+                            .ok_or_else(|| ConfigError::UnknownKey(prefix.clone()))?;
+                        let settings = parse_settings(table, &prefix, |_, _| Ok(false))?;
+                        profiles.insert(name.to_string(), settings);
+                    }
+                }
+                _ => return Ok(false),
+            }
+
+            Ok(true)
+        })?;
+        settings.profile = profile;
+
+        Ok(Self {
+            document,
+            settings,
+            profiles,
+        })
+    }
+
+    /// Reads the configuration file at `path`. A missing file is treated as
+    /// an empty one.
+    ///
+    /// # Errors
+    /// This function may return an error if the file exists but can't be
+    /// read or parsed.
+    pub fn read<P: AsRef<Path>>(path: P) -> Result<Self, ConfigError> {
+        match fs::read_to_string(path) {
+            Ok(source) => Self::parse(&source),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Reads the configuration file at [`Config::path`], if there is one.
+    ///
+    /// # Errors
+    /// This function may return an error if the file exists but can't be
+    /// read or parsed.
+    pub fn load() -> Result<Self, ConfigError> {
+        match Config::path() {
+            Some(path) => Self::read(path),
+            None => Ok(Self::default()),
+        }
+    }
+
+    /// Writes the configuration file to `path`, creating its folder if
+    /// needed.
+    ///
+    /// # Errors
+    /// This function may return an error if the file can't be written.
+    pub fn write<P: AsRef<Path>>(&self, path: P) -> Result<(), io::Error> {
+        let path = path.as_ref();
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        fs::write(path, self.document.to_string())
+    }
+
+    /// Returns the top-level settings, with [`Config::profile`] set to the
+    /// default profile.
+    pub fn settings(&self) -> &Config {
+        &self.settings
+    }
+
+    /// Returns the settings of the profile called `name`.
+    pub fn profile(&self, name: &str) -> Option<&Config> {
+        self.profiles.get(name)
+    }
+
+    /// Returns every profile with its settings, sorted by name.
+    pub fn profiles(&self) -> &BTreeMap<String, Config> {
+        &self.profiles
+    }
+
+    /// Completes `settings` with the ones of the file: first those of the
+    /// selected profile, then the top-level ones.
+    ///
+    /// The selected profile is the one of `settings`, or else the default
+    /// one.
+    ///
+    /// # Errors
+    /// This function may return an error if the selected profile doesn't
+    /// exist.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use std::path::PathBuf;
+    ///
+    /// use user_registry_lib::{Config, ConfigFile};
+    ///
+    /// let file = ConfigFile::parse(
+    ///     "profile = \"a\"\nbase_dn = \"dc=top\"\n[profiles.a]\ndata = \"a.json\"\n[profiles.b]\ndata = \"b.json\"",
+    /// )
+    /// .unwrap();
+    ///
+    /// let config = file.resolve(Config::default()).unwrap();
+    /// assert_eq!(config.data, Some(PathBuf::from("a.json")));
+    /// assert_eq!(config.base_dn.as_deref(), Some("dc=top"));
+    ///
+    /// let b = Config { profile: Some(String::from("b")), ..Config::default() };
+    /// assert_eq!(file.resolve(b).unwrap().data, Some(PathBuf::from("b.json")));
+    /// ```
+    pub fn resolve(&self, settings: Config) -> Result<Config, ConfigError> {
+        let settings = settings.or(Config {
+            profile: self.settings.profile.clone(),
+            ..Config::default()
+        });
+
+        let profile = match &settings.profile {
+            Some(name) => self
+                .profile(name)
+                .cloned()
+                .ok_or_else(|| ConfigError::UnknownProfile(name.clone()))?,
+            None => Config::default(),
+        };
+
+        Ok(settings.or(profile).or(self.settings.clone()))
+    }
+
+    /// Adds a profile called `name` with the given settings. The
+    /// [`Config::profile`] of `settings` is ignored.
+    ///
+    /// # Errors
+    /// This function may return an error if the profile already exists.
+    pub fn add_profile(&mut self, name: &str, settings: Config) -> Result<(), ConfigError> {
+        if self.profiles.contains_key(name) {
+            return Err(ConfigError::ProfileExists(name.to_string()));
+        }
+
+        let mut table = Table::new();
+        if let Some(data) = &settings.data {
+            table.insert("data", value(data.to_string_lossy().into_owned()));
+        }
+        if let Some(format) = &settings.format {
+            table.insert("format", value(enum_name(format)));
+        }
+        if let Some(policy) = &settings.policy {
+            table.insert("policy", value(enum_name(policy)));
+        }
+        if let Some(base_dn) = &settings.base_dn {
+            table.insert("base_dn", value(base_dn));
+        }
+
+        let profiles = self.document.entry("profiles").or_insert_with(|| {
+            let mut profiles = Table::new();
+            profiles.set_implicit(true);
+            Item::Table(profiles)
+        });
+
+        if let Some(profiles) = profiles.as_table_like_mut() {
+            profiles.insert(name, Item::Table(table));
+        }
+
+        self.profiles.insert(
+            name.to_string(),
+            Config {
+                profile: None,
+                ..settings
+            },
+        );
+        Ok(())
+    }
+
+    /// Removes the profile called `name` and returns its settings. If it was
+    /// the default profile, no profile is selected by default anymore.
+    ///
+    /// # Errors
+    /// This function may return an error if the profile doesn't exist.
+    pub fn remove_profile(&mut self, name: &str) -> Result<Config, ConfigError> {
+        let settings = self
+            .profiles
+            .remove(name)
+            .ok_or_else(|| ConfigError::UnknownProfile(name.to_string()))?;
+
+        if let Some(profiles) = self
+            .document
+            .get_mut("profiles")
+            .and_then(Item::as_table_like_mut)
+        {
+            profiles.remove(name);
+        }
+
+        if self.settings.profile.as_deref() == Some(name) {
+            self.set_default_profile(None)?;
+        }
+
+        Ok(settings)
+    }
+
+    /// Sets the profile selected by default, or unsets it if `name` is
+    /// [`None`].
+    ///
+    /// # Errors
+    /// This function may return an error if the profile doesn't exist.
+    pub fn set_default_profile(&mut self, name: Option<&str>) -> Result<(), ConfigError> {
+        match name {
+            Some(name) if !self.profiles.contains_key(name) => {
+                return Err(ConfigError::UnknownProfile(name.to_string()));
+            }
+            Some(name) => {
+                self.document.insert("profile", value(name));
+            }
+            None => {
+                self.document.remove("profile");
+            }
+        }
+
+        self.settings.profile = name.map(str::to_string);
+        Ok(())
+    }
+}
+
+impl Display for ConfigFile {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.document)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests parsing the settings of a configuration file.
+    ///
+    /// This test ensures that:
+    /// - Every supported key of a configuration file is read.
+    /// - Enum values are matched case-insensitively.
+    /// - An empty file sets nothing.
+    #[test]
+    fn parse_config_file() {
+        let file = ConfigFile::parse(
+            "data = \"/tmp/users.json\"\nformat = \"JSON\"\npolicy = \"keep-both\"\nbase_dn = \"dc=example\"\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            file.settings(),
+            &Config {
+                profile: None,
+                data: Some(PathBuf::from("/tmp/users.json")),
+                format: Some(OutputFormat::Json),
+                policy: Some(ConflictPolicy::KeepBoth),
+                base_dn: Some(String::from("dc=example")),
+            }
+        );
+        assert_eq!(
+            ConfigFile::parse("").unwrap().settings(),
+            &Config::default()
+        );
+    }
+
+    /// Tests rejecting invalid configuration files.
+    ///
+    /// This test ensures that:
+    /// - Unknown keys are rejected.
+    /// - Values of the wrong type or outside the supported ones are rejected.
+    /// - Invalid TOML is rejected.
+    /// - Errors in profiles name the profile.
+    #[test]
+    fn reject_invalid_config_file() {
+        assert!(matches!(
+            ConfigFile::parse("colour = 1"),
+            Err(ConfigError::UnknownKey(key)) if key == "colour"
+        ));
+        assert!(matches!(
+            ConfigFile::parse("data = 3"),
+            Err(ConfigError::InvalidValue { setting, value }) if setting == "data" && value == "3"
+        ));
+        assert!(matches!(
+            ConfigFile::parse("policy = \"newest\""),
+            Err(ConfigError::InvalidValue { setting, .. }) if setting == "policy"
+        ));
+        assert!(matches!(
+            ConfigFile::parse("data = "),
+            Err(ConfigError::Syntax(_))
+        ));
+        assert!(matches!(
+            ConfigFile::parse("[profiles.staff]\nprofile = \"staff\""),
+            Err(ConfigError::UnknownKey(key)) if key == "profiles.staff.profile"
+        ));
+        assert!(matches!(
+            ConfigFile::parse("[profiles.staff]\nformat = \"xml\""),
+            Err(ConfigError::InvalidValue { setting, .. }) if setting == "profiles.staff.format"
+        ));
+    }
+
+    /// Tests resolving the settings of the selected profile.
+    ///
+    /// This test ensures that:
+    /// - Profiles and the default profile are read.
+    /// - The selected profile takes precedence over top-level settings.
+    /// - Given settings take precedence over the profile.
+    /// - Selecting an unknown profile is an error.
+    #[test]
+    fn resolve_profiles() {
+        let file = ConfigFile::parse(
+            r#"
+profile = "staff"
+format = "json"
+policy = "keep-both"
+
+[profiles.staff]
+data = "staff.json"
+policy = "take-theirs"
+
+[profiles.contractors]
+data = "contractors.json"
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(file.settings().profile.as_deref(), Some("staff"));
+        assert_eq!(
+            file.profiles().keys().collect::<Vec<_>>(),
+            ["contractors", "staff"]
+        );
+
+        assert_eq!(
+            file.resolve(Config::default()).unwrap(),
+            Config {
+                profile: Some(String::from("staff")),
+                data: Some(PathBuf::from("staff.json")),
+                format: Some(OutputFormat::Json),
+                policy: Some(ConflictPolicy::TakeTheirs),
+                base_dn: None,
+            }
+        );
+
+        let config = file
+            .resolve(Config {
+                profile: Some(String::from("contractors")),
+                format: Some(OutputFormat::Text),
+                ..Config::default()
+            })
+            .unwrap();
+        assert_eq!(config.data, Some(PathBuf::from("contractors.json")));
+        assert_eq!(config.format, Some(OutputFormat::Text));
+        assert_eq!(config.policy, Some(ConflictPolicy::KeepBoth));
+
+        assert!(matches!(
+            file.resolve(Config {
+                profile: Some(String::from("fixtures")),
+                ..Config::default()
+            }),
+            Err(ConfigError::UnknownProfile(name)) if name == "fixtures"
+        ));
+    }
+
+    /// Tests the order of the settings of a profile selected on the command
+    /// line and of the environment variables.
+    ///
+    /// This test ensures that:
+    /// - The data file of a profile selected on the command line overrides
+    ///   the one of the environment variables.
+    /// - The environment variables still fill the settings that the profile
+    ///   doesn't set, before the top-level ones.
+    /// - Without a profile on the command line, the environment variables
+    ///   override the default profile.
+    #[test]
+    fn selected_profile_overrides_environment() {
+        let file = ConfigFile::parse(
+            r#"
+profile = "staff"
+format = "json"
+base_dn = "dc=top"
+
+[profiles.staff]
+data = "staff.json"
+"#,
+        )
+        .unwrap();
+        let env = Config {
+            data: Some(PathBuf::from("env.json")),
+            base_dn: Some(String::from("dc=env")),
+            ..Config::default()
+        };
+
+        let config = Config::resolve(&file, Some("staff"), env.clone()).unwrap();
+        assert_eq!(config.profile.as_deref(), Some("staff"));
+        assert_eq!(config.data, Some(PathBuf::from("staff.json")));
+        assert_eq!(config.base_dn.as_deref(), Some("dc=env"));
+        assert_eq!(config.format, Some(OutputFormat::Json));
+
+        let config = Config::resolve(&file, None, env).unwrap();
+        assert_eq!(config.data, Some(PathBuf::from("env.json")));
+
+        assert!(matches!(
+            Config::resolve(&file, Some("fixtures"), Config::default()),
+            Err(ConfigError::UnknownProfile(name)) if name == "fixtures"
+        ));
+    }
+
+    /// Tests adding and removing profiles of a configuration file.
+    ///
+    /// This test ensures that:
+    /// - Added profiles are written to the file and can be read back.
+    /// - Adding an existing profile is an error.
+    /// - Removing the default profile unsets the default.
+    /// - Comments of the file are kept.
+    #[test]
+    fn edit_profiles() {
+        let mut file = ConfigFile::parse("# Registry settings\nformat = \"json\"\n").unwrap();
+        let fixtures = Config {
+            data: Some(PathBuf::from("fixtures.json")),
+            policy: Some(ConflictPolicy::MatchByEmail),
+            ..Config::default()
+        };
+
+        file.add_profile("fixtures", fixtures.clone()).unwrap();
+        file.set_default_profile(Some("fixtures")).unwrap();
+        assert!(matches!(
+            file.add_profile("fixtures", Config::default()),
+            Err(ConfigError::ProfileExists(_))
+        ));
+        assert!(matches!(
+            file.set_default_profile(Some("staff")),
+            Err(ConfigError::UnknownProfile(_))
+        ));
+
+        let source = file.to_string();
+        assert!(source.starts_with("# Registry settings\n"));
+
+        let mut file = ConfigFile::parse(&source).unwrap();
+        assert_eq!(file.profile("fixtures"), Some(&fixtures));
+        assert_eq!(file.settings().profile.as_deref(), Some("fixtures"));
+
+        assert_eq!(file.remove_profile("fixtures").unwrap(), fixtures);
+        assert_eq!(file.settings().profile, None);
+
+        let file = ConfigFile::parse(&file.to_string()).unwrap();
+        assert!(file.profiles().is_empty());
+        assert_eq!(file.settings().profile, None);
+        assert_eq!(file.settings().format, Some(OutputFormat::Json));
+    }
+
+    /// Tests reading the settings from the environment variables.
+    ///
+    /// This test ensures that:
+    /// - Settings are read from their environment variables.
+    /// - Empty variables are ignored.
+    /// - Invalid values name the offending variable.
+    #[test]
+    fn read_environment_variables() {
+        let config = Config::from_vars(|name| match name {
+            PROFILE_VAR => Some(String::from("staff")),
+            DATA_VAR => Some(String::from("env.json")),
+            POLICY_VAR => Some(String::from("take-theirs")),
+            BASE_DN_VAR => Some(String::new()),
+            _ => None,
+        })
+        .unwrap();
+
+        assert_eq!(
+            config,
+            Config {
+                profile: Some(String::from("staff")),
+                data: Some(PathBuf::from("env.json")),
+                policy: Some(ConflictPolicy::TakeTheirs),
+                ..Config::default()
+            }
+        );
+
+        assert!(matches!(
+            Config::from_vars(|name| (name == FORMAT_VAR).then(|| String::from("yaml"))),
+            Err(ConfigError::InvalidValue { setting, .. }) if setting == FORMAT_VAR
+        ));
+    }
+
+    /// Tests finding and preparing the data file.
+    ///
+    /// This test ensures that:
+    /// - The data file given overrides the configured one.
+    /// - Finding the data file doesn't create its folder.
+    /// - Preparing the data file creates its folder.
+    /// - A path that is not a file is rejected.
+    #[test]
+    fn prepare_data_file() {
+        let folder = env::temp_dir().join("users_registry_prepare_data_file_test");
+        let _ = fs::remove_dir_all(&folder);
+        let config = Config {
+            data: Some(folder.join("configured.json")),
+            ..Config::default()
+        };
+
+        let data_file = folder.join("nested").join("users.json");
+        assert_eq!(
+            config.find_data_file(Some(data_file.clone())).unwrap(),
+            data_file
+        );
+        assert!(!folder.exists());
+        assert_eq!(
+            config.prepare_data_file(Some(data_file.clone())).unwrap(),
+            data_file
+        );
+        assert!(folder.join("nested").is_dir());
+
+        assert_eq!(
+            config.prepare_data_file(None).unwrap(),
+            folder.join("configured.json")
+        );
+        assert!(matches!(
+            config.prepare_data_file(Some(folder.join("nested"))),
+            Err(DataFileError::NotAFile)
+        ));
+
+        fs::remove_dir_all(&folder).unwrap();
+    }
+
+    /// Tests reading a configuration file that doesn't exist.
+    ///
+    /// This test ensures that:
+    /// - A missing configuration file is treated as an empty one.
+    #[test]
+    fn read_missing_config_file() {
+        let path = env::temp_dir().join("users_registry_missing_config.toml");
+        assert_eq!(
+            ConfigFile::read(path).unwrap().settings(),
+            &Config::default()
+        );
+    }
+}
@@ -32,7 +32,7 @@ use crate::User;
 /// ```
 ///
 /// [users]: User
-#[derive(Default, Debug, Serialize)]
+#[derive(Default, Debug, Clone, Serialize)]
 pub struct Data {
     /// The next available unique ID to be assigned to a user.
     #[serde(skip)]
@@ -111,6 +111,32 @@ impl Data {
         id
     }
 
+    /// Inserts a user with a specific ID into the `Data` structure.
+    ///
+    /// If a user already exists with the given ID, it is replaced and the
+    /// previous user is returned. Otherwise, [`None`] is returned.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use user_registry_lib::{Data, User};
+    /// let mut data = Data::new();
+    /// let user = User {
+    ///     first_name: "John".to_string(),
+    ///     last_name: "Doe".to_string(),
+    ///     email: "john@example.com".to_string(),
+    ///     phone_number: "555-1234".to_string(),
+    /// };
+    ///
+    /// assert_eq!(data.insert_user(7, user.clone()), None);
+    /// assert_eq!(data.user(7), Some(&user));
+    /// assert_eq!(data.add_user(user), 0);
+    /// ```
+    pub fn insert_user(&mut self, id: usize, user: User) -> Option<User> {
+        let previous = self.users.insert(id, user);
+        self.calculate_next_id();
+        previous
+    }
+
     /// Retrieves a user by their ID.
     ///
     /// This method looks up a user by their unique ID. If the user exists, it
@@ -364,4 +390,23 @@ mod tests {
         // Verify that the next available ID is set back to the ID of the removed user
         assert_eq!(data.next_id, id);
     }
+
+    /// Tests inserting [`User`]s with a specific ID.
+    ///
+    /// This test ensures that:
+    /// - Inserting into a free ID returns [`None`] and skips it for new users.
+    /// - Inserting into a used ID replaces and returns the previous [`User`].
+    #[test]
+    fn insert_user_with_id() {
+        let mut data = Data::new();
+
+        // Insert a user in the first ID and check it is not reused.
+        assert_eq!(data.insert_user(0, first_user()), None);
+        assert_eq!(data.next_id, 1);
+
+        // Replace the user and check the previous one is returned.
+        assert_eq!(data.insert_user(0, second_user()), Some(first_user()));
+        assert_eq!(data.user(0), Some(&second_user()));
+        assert_eq!(data.users().len(), 1);
+    }
 }
@@ -0,0 +1,254 @@
+use serde::{Serialize, Serializer, ser::SerializeSeq};
+
+use crate::{Data, Field, User};
+
+/// A change to a single field of a [`User`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Change {
+    /// The field that changed.
+    pub field: Field,
+
+    /// The value of the field before the change.
+    pub old: String,
+
+    /// The value of the field after the change.
+    pub new: String,
+}
+
+/// A [`User`] present in both [`Data`]s whose fields differ.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Modification {
+    /// The ID of the modified [`User`].
+    pub id: usize,
+
+    /// The fields that changed, in declaration order.
+    pub changes: Vec<Change>,
+}
+
+/// The differences between two [`Data`] values.
+///
+/// [`User`]s are matched by their ID: IDs only present in the new [`Data`]
+/// are reported as added, IDs only present in the old [`Data`] are reported
+/// as removed, and IDs present in both with different fields are reported as
+/// modified along with each changed field. Every list is sorted by ID.
+///
+/// # Examples
+/// ```rust
+/// # use user_registry_lib::{Data, Diff, User};
+/// let user = User {
+///     first_name: "John".to_string(),
+///     last_name: "Doe".to_string(),
+///     email: "john@example.com".to_string(),
+///     phone_number: "555-1234".to_string(),
+/// };
+///
+/// let old = Data::new();
+/// let mut new = Data::new();
+/// new.add_user(user.clone());
+///
+/// let diff = Diff::new(&old, &new);
+/// assert_eq!(diff.added, vec![(0, user)]);
+/// assert!(diff.removed.is_empty());
+/// assert!(diff.modified.is_empty());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct Diff {
+    /// The [`User`]s only present in the new [`Data`].
+    #[serde(serialize_with = "serialize_users")]
+    pub added: Vec<(usize, User)>,
+
+    /// The [`User`]s only present in the old [`Data`].
+    #[serde(serialize_with = "serialize_users")]
+    pub removed: Vec<(usize, User)>,
+
+    /// The [`User`]s present in both [`Data`]s with different fields.
+    pub modified: Vec<Modification>,
+}
+
+impl Diff {
+    /// Computes the differences needed to go from `old` to `new`.
+    pub fn new(old: &Data, new: &Data) -> Self {
+        let mut diff = Self::default();
+
+        for (id, old_user) in old.users() {
+            match new.user(id) {
+                None => diff.removed.push((id, old_user.clone())),
+                Some(new_user) => {
+                    let changes: Vec<_> = Field::ALL
+                        .into_iter()
+                        .filter(|field| field.value(old_user) != field.value(new_user))
+                        .map(|field| Change {
+                            field,
+                            old: field.value(old_user).to_string(),
+                            new: field.value(new_user).to_string(),
+                        })
+                        .collect();
+
+                    if !changes.is_empty() {
+                        diff.modified.push(Modification { id, changes });
+                    }
+                }
+            }
+        }
+
+        for (id, new_user) in new.users() {
+            if old.user(id).is_none() {
+                diff.added.push((id, new_user.clone()));
+            }
+        }
+
+        diff
+    }
+
+    /// Returns `true` if both [`Data`]s contain the same [`User`]s.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.modified.is_empty()
+    }
+}
+
+/// Serializes a list of [`User`]s along with their ID, using the full field
+/// names instead of the abbreviated ones used in the data file.
+fn serialize_users<S: Serializer>(
+    users: &[(usize, User)],
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    #[derive(Serialize)]
+    struct Entry<'a> {
+        id: usize,
+        first_name: &'a str,
+        last_name: &'a str,
+        email: &'a str,
+        phone_number: &'a str,
+    }
+
+    let mut seq = serializer.serialize_seq(Some(users.len()))?;
+
+    for (id, user) in users {
+        seq.serialize_element(&Entry {
+            id: *id,
+            first_name: &user.first_name,
+            last_name: &user.last_name,
+            email: &user.email,
+            phone_number: &user.phone_number,
+        })?;
+    }
+
+    seq.end()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Helper function to create a [`User`] with data for testing purposes.
+    fn first_user() -> User {
+        User {
+            first_name: String::from("firstName"),
+            last_name: String::from("firstSurname"),
+            email: String::from("firstEmail"),
+            phone_number: String::from("0123456789"),
+        }
+    }
+
+    /// Helper function to create a [`User`] with data for testing purposes.
+    fn second_user() -> User {
+        User {
+            first_name: String::from("secondName"),
+            last_name: String::from("secondSurname"),
+            email: String::from("secondEmail"),
+            phone_number: String::from("9786543210"),
+        }
+    }
+
+    /// Tests computing the [`Diff`] between two [`Data`]s.
+    ///
+    /// This test ensures that:
+    /// - [`User`]s only in the new [`Data`] are reported as added.
+    /// - [`User`]s only in the old [`Data`] are reported as removed.
+    /// - Only the changed fields of a modified [`User`] are reported.
+    #[test]
+    fn diff_added_removed_modified() {
+        let mut old = Data::new();
+        old.add_user(first_user());
+        old.add_user(second_user());
+
+        let mut modified = first_user();
+        modified.email = String::from("newEmail");
+
+        let mut new = Data::new();
+        new.add_user(modified);
+        new.add_user(first_user());
+        new.add_user(second_user());
+
+        let diff = Diff::new(&old, &new);
+
+        assert_eq!(diff.added, vec![(2, second_user())]);
+        assert!(diff.removed.is_empty());
+        assert_eq!(
+            diff.modified,
+            vec![
+                Modification {
+                    id: 0,
+                    changes: vec![Change {
+                        field: Field::Email,
+                        old: String::from("firstEmail"),
+                        new: String::from("newEmail"),
+                    }],
+                },
+                Modification {
+                    id: 1,
+                    changes: Field::ALL
+                        .into_iter()
+                        .map(|field| Change {
+                            field,
+                            old: field.value(&second_user()).to_string(),
+                            new: field.value(&first_user()).to_string(),
+                        })
+                        .collect(),
+                },
+            ]
+        );
+
+        let reverse = Diff::new(&new, &old);
+        assert_eq!(reverse.removed, vec![(2, second_user())]);
+    }
+
+    /// Tests that identical [`Data`]s produce an empty [`Diff`].
+    #[test]
+    fn diff_identical_data() {
+        let mut data = Data::new();
+        data.add_user(first_user());
+
+        let mut other = Data::new();
+        other.add_user(first_user());
+
+        assert!(Diff::new(&data, &other).is_empty());
+    }
+
+    /// Tests serializing a [`Diff`] to JSON.
+    ///
+    /// This test ensures that [`User`]s and fields use their full names.
+    #[test]
+    fn diff_to_json() {
+        let old = Data::new();
+        let mut new = Data::new();
+        new.add_user(first_user());
+
+        let json = serde_json::to_value(Diff::new(&old, &new)).unwrap();
+
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "added": [{
+                    "id": 0,
+                    "first_name": "firstName",
+                    "last_name": "firstSurname",
+                    "email": "firstEmail",
+                    "phone_number": "0123456789",
+                }],
+                "removed": [],
+                "modified": [],
+            })
+        );
+    }
+}
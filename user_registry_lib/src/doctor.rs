@@ -0,0 +1,518 @@
+use std::fmt::{self, Display, Formatter};
+
+use serde::{
+    Deserialize, Deserializer,
+    de::{self, IgnoredAny, MapAccess, Visitor},
+};
+use serde_json::Value;
+
+use crate::{Data, User, ValidationError};
+
+/// A problem found in the contents of a data file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Problem {
+    /// The file is not valid JSON.
+    Syntax {
+        line: usize,
+        column: usize,
+        message: String,
+    },
+
+    /// The file is valid JSON, but not a registry of [`User`]s.
+    InvalidStructure(String),
+
+    /// A record can't be read as a [`User`], so it can't be salvaged.
+    InvalidRecord { id: String, message: String },
+
+    /// A [`User`] has a field that isn't valid.
+    InvalidUser { id: usize, error: ValidationError },
+
+    /// Several records have the same ID. The later one is salvaged with the
+    /// `new_id`, or dropped if it holds the same [`User`].
+    DuplicateId { id: usize, new_id: Option<usize> },
+
+    /// A [`User`] has the same fields as the one with the `original` ID.
+    DuplicateUser { id: usize, original: usize },
+}
+
+impl Problem {
+    /// Returns whether saving the salvaged [`Data`] fixes the problem. The
+    /// others must be fixed by editing the [`User`]s.
+    pub fn is_repairable(&self) -> bool {
+        !matches!(self, Self::InvalidUser { .. } | Self::DuplicateUser { .. })
+    }
+}
+
+impl Display for Problem {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Syntax {
+                line,
+                column,
+                message,
+            } => write!(f, "Line {line}, column {column}: {message}."),
+            Self::InvalidStructure(message) => {
+                write!(f, "The file is not a registry of users: {message}.")
+            }
+            Self::InvalidRecord { id, message } => {
+                write!(f, "The record '{id}' is not a user: {message}.")
+            }
+            Self::InvalidUser { id, error } => write!(f, "The user {id} is invalid: {error}"),
+            Self::DuplicateId {
+                id,
+                new_id: Some(new_id),
+            } => write!(
+                f,
+                "The ID {id} is used by several records; the later one gets the ID {new_id}."
+            ),
+            Self::DuplicateId { id, new_id: None } => {
+                write!(f, "The record {id} appears twice; the copy is dropped.")
+            }
+            Self::DuplicateUser { id, original } => {
+                write!(
+                    f,
+                    "The user {id} has the same fields as the user {original}."
+                )
+            }
+        }
+    }
+}
+
+/// The records of a registry, in the order of the file and with repeated IDs.
+struct Records(Vec<(String, Value)>);
+
+impl<'de> Deserialize<'de> for Records {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct RecordsVisitor;
+
+        impl<'de> Visitor<'de> for RecordsVisitor {
+            type Value = Records;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                write!(formatter, "a map of users")
+            }
+
+            fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+            where
+                A: MapAccess<'de>,
+            {
+                let mut records = Vec::new();
+
+                while let Some(entry) = map.next_entry()? {
+                    records.push(entry);
+                }
+
+                Ok(Records(records))
+            }
+        }
+
+        deserializer.deserialize_map(RecordsVisitor)
+    }
+}
+
+/// A registry read like [`Data`], keeping the records that [`Data`] would
+/// reject or overwrite.
+struct Registry(Records);
+
+impl<'de> Deserialize<'de> for Registry {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct RegistryVisitor;
+
+        impl<'de> Visitor<'de> for RegistryVisitor {
+            type Value = Registry;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                write!(formatter, "a Data struct")
+            }
+
+            fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+            where
+                A: MapAccess<'de>,
+            {
+                let mut records = None;
+
+                while let Some(key) = map.next_key()? {
+                    if let "u" = key {
+                        if records.is_some() {
+                            return Err(de::Error::duplicate_field("u"));
+                        }
+                        records = Some(map.next_value()?);
+                    } else {
+                        let _: IgnoredAny = map.next_value()?;
+                    }
+                }
+
+                records
+                    .map(Registry)
+                    .ok_or_else(|| de::Error::missing_field("u"))
+            }
+        }
+
+        deserializer.deserialize_struct("Data", &["u"], RegistryVisitor)
+    }
+}
+
+/// Returns whether a quote right after `text` is escaped, which is when `text`
+/// ends with an odd number of backslashes.
+fn is_escaped(text: &str) -> bool {
+    text.bytes().rev().take_while(|&byte| byte == b'\\').count() % 2 == 1
+}
+
+/// Finds the records of contents that can't be read as a whole: every
+/// `"id": {...}` object that is complete.
+fn scan_records(contents: &str) -> Vec<(String, Value)> {
+    let mut records = Vec::new();
+    let mut index = 0;
+
+    while let Some(start) = contents[index..].find('"') {
+        let start = index + start;
+        index = start + 1;
+
+        // Skips the escaped quotes of strings.
+        if is_escaped(&contents[..start]) {
+            continue;
+        }
+
+        let Some(length) = contents[index..].find('"') else {
+            break;
+        };
+        let key = &contents[index..index + length];
+
+        if key.is_empty() || !key.bytes().all(|byte| byte.is_ascii_digit()) {
+            continue;
+        }
+
+        let rest = contents[index + length + 1..].trim_start();
+        let Some(rest) = rest.strip_prefix(':').map(str::trim_start) else {
+            continue;
+        };
+
+        if !rest.starts_with('{') {
+            continue;
+        }
+
+        let mut values = serde_json::Deserializer::from_str(rest).into_iter::<Value>();
+
+        if let Some(Ok(value)) = values.next() {
+            records.push((key.to_string(), value));
+            index = contents.len() - rest.len() + values.byte_offset();
+        }
+    }
+
+    records
+}
+
+/// The result of checking the contents of a data file: the problems found and
+/// the [`User`]s that could be salvaged.
+#[derive(Debug, Clone, Default)]
+pub struct Diagnosis {
+    /// The problems found, in the order of the file.
+    pub problems: Vec<Problem>,
+
+    /// The [`User`]s that could be read, with repeated IDs given new ones.
+    pub data: Data,
+}
+
+impl Diagnosis {
+    /// Checks the `contents` of a data file.
+    ///
+    /// Contents that are not valid JSON are reported with the line and column
+    /// of the error, and every complete record is salvaged from them. Records
+    /// that are not [`User`]s, repeated IDs, invalid [`User`]s and duplicated
+    /// [`User`]s are reported too.
+    ///
+    /// Empty contents are an empty registry, as for
+    /// [`read_data`](crate::command::read_data).
+    ///
+    /// # Examples
+    /// ```rust
+    /// use user_registry_lib::{Diagnosis, Problem};
+    ///
+    /// let contents = r#"{"u":{"0":{"n":"Ann","s":"Lee","e":"ann@example.com","p":"555"},"1":{"n":"#;
+    /// let diagnosis = Diagnosis::check(contents);
+    ///
+    /// assert!(matches!(diagnosis.problems[0], Problem::Syntax { line: 1, .. }));
+    /// assert_eq!(diagnosis.data.users().len(), 1);
+    /// ```
+    pub fn check(contents: &str) -> Self {
+        let mut problems = Vec::new();
+
+        if contents.is_empty() {
+            return Self::default();
+        }
+
+        let records = match serde_json::from_str(contents) {
+            Ok(Registry(Records(records))) => records,
+            Err(err) => {
+                let message = err.to_string();
+                let position = format!(" at line {} column {}", err.line(), err.column());
+                let message = message
+                    .strip_suffix(&position)
+                    .unwrap_or(&message)
+                    .to_string();
+
+                problems.push(if err.is_data() {
+                    Problem::InvalidStructure(message)
+                } else {
+                    Problem::Syntax {
+                        line: err.line(),
+                        column: err.column(),
+                        message,
+                    }
+                });
+
+                scan_records(contents)
+            }
+        };
+
+        let mut data = Data::default();
+        let mut repeated = Vec::new();
+
+        for (key, value) in records {
+            let Ok(id) = key.parse() else {
+                problems.push(Problem::InvalidRecord {
+                    id: key,
+                    message: String::from("the ID is not a number"),
+                });
+                continue;
+            };
+
+            let user: User = match serde_json::from_value(value) {
+                Ok(user) => user,
+                Err(err) => {
+                    problems.push(Problem::InvalidRecord {
+                        id: key,
+                        message: err.to_string(),
+                    });
+                    continue;
+                }
+            };
+
+            match data.user(id) {
+                Some(existing) if *existing == user => {
+                    problems.push(Problem::DuplicateId { id, new_id: None });
+                }
+                Some(_) => repeated.push((id, user)),
+                None => {
+                    data.insert_user(id, user);
+                }
+            }
+        }
+
+        for (id, user) in repeated {
+            let new_id = data.add_user(user);
+            problems.push(Problem::DuplicateId {
+                id,
+                new_id: Some(new_id),
+            });
+        }
+
+        let users = data.users();
+        for (index, (id, user)) in users.iter().enumerate() {
+            if let Err(error) = user.validate() {
+                problems.push(Problem::InvalidUser { id: *id, error });
+            }
+
+            if let Some((original, _)) = users[..index].iter().find(|(_, other)| other == user) {
+                problems.push(Problem::DuplicateUser {
+                    id: *id,
+                    original: *original,
+                });
+            }
+        }
+
+        Self { problems, data }
+    }
+
+    /// Returns whether no problems were found.
+    pub fn is_healthy(&self) -> bool {
+        self.problems.is_empty()
+    }
+
+    /// Returns whether some problems are fixed by saving the salvaged
+    /// [`Data`].
+    pub fn is_repairable(&self) -> bool {
+        self.problems.iter().any(Problem::is_repairable)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Field;
+
+    use super::*;
+
+    /// Helper function to create a [`User`] with data for testing purposes.
+    fn first_user() -> User {
+        User {
+            first_name: String::from("John"),
+            last_name: String::from("Doe"),
+            email: String::from("john.doe@example.com"),
+            phone_number: String::from("+1 555 0100"),
+        }
+    }
+
+    /// Helper function to create a [`User`] with data for testing purposes.
+    fn second_user() -> User {
+        User {
+            first_name: String::from("Jane"),
+            last_name: String::from("Smith"),
+            email: String::from("jane.smith@example.com"),
+            phone_number: String::from("+1 555 0101"),
+        }
+    }
+
+    /// Helper function to write a [`User`] as a record of a data file, with
+    /// its ID as the key.
+    fn record(id: usize, user: &User) -> String {
+        format!("\"{id}\":{}", serde_json::to_string(user).unwrap())
+    }
+
+    /// Tests checking the contents of healthy data files.
+    ///
+    /// This test ensures that:
+    /// - Empty contents and valid registries have no problems.
+    /// - The users of a valid registry are kept with their IDs.
+    #[test]
+    fn check_healthy_contents() {
+        assert!(Diagnosis::check("").is_healthy());
+
+        let contents = format!(
+            "{{\"u\":{{{},{}}}}}",
+            record(0, &first_user()),
+            record(3, &second_user())
+        );
+        let diagnosis = Diagnosis::check(&contents);
+
+        assert!(diagnosis.is_healthy());
+        assert_eq!(
+            diagnosis.data.users(),
+            vec![(0, &first_user()), (3, &second_user())]
+        );
+    }
+
+    /// Tests salvaging the users of a truncated data file.
+    ///
+    /// This test ensures that:
+    /// - Syntax errors are reported with their line and column.
+    /// - Complete records are salvaged from truncated contents.
+    /// - Strings that look like records are not salvaged.
+    #[test]
+    fn salvage_truncated_contents() {
+        let mut user = first_user();
+        user.last_name = String::from("\"2\": {");
+        let contents = format!(
+            "{{\"u\": {{\n  {},\n  {},\n  \"5\": {{\"n\": \"Jo",
+            record(0, &user),
+            record(1, &second_user())
+        );
+        let diagnosis = Diagnosis::check(&contents);
+
+        assert_eq!(
+            diagnosis.problems,
+            vec![Problem::Syntax {
+                line: 4,
+                column: 16,
+                message: String::from("EOF while parsing a string"),
+            }]
+        );
+        assert!(diagnosis.is_repairable());
+        assert_eq!(
+            diagnosis.data.users(),
+            vec![(0, &user), (1, &second_user())]
+        );
+    }
+
+    /// Tests detecting escaped quotes while scanning for records.
+    ///
+    /// This test ensures that:
+    /// - A quote after a single backslash is escaped.
+    /// - A quote after an escaped backslash ends the string.
+    #[test]
+    fn detect_escaped_quotes() {
+        assert!(!is_escaped(r#"{"n": "Doe"#));
+        assert!(is_escaped(r#"{"n": "Doe\"#));
+        assert!(!is_escaped(r#"{"n": "Doe\\"#));
+        assert!(is_escaped(r#"{"n": "Doe\\\"#));
+    }
+
+    /// Tests reporting records that aren't valid users.
+    ///
+    /// This test ensures that:
+    /// - Valid JSON that isn't a registry is reported.
+    /// - Records that aren't users are reported and dropped.
+    #[test]
+    fn report_invalid_records() {
+        let diagnosis = Diagnosis::check("[]");
+        assert!(matches!(
+            diagnosis.problems[..],
+            [Problem::InvalidStructure(_)]
+        ));
+
+        let contents = format!(
+            "{{\"u\":{{\"a\":{{}},\"1\":{{\"n\":\"Ann\"}},{}}}}}",
+            record(2, &first_user())
+        );
+        let diagnosis = Diagnosis::check(&contents);
+
+        assert!(matches!(
+            &diagnosis.problems[..],
+            [
+                Problem::InvalidRecord { id: first, .. },
+                Problem::InvalidRecord { id: second, .. },
+            ] if first == "a" && second == "1"
+        ));
+        assert_eq!(diagnosis.data.users(), vec![(2, &first_user())]);
+    }
+
+    /// Tests reporting repeated IDs and users.
+    ///
+    /// This test ensures that:
+    /// - Repeated IDs with different users give the later one a new ID.
+    /// - Repeated IDs with the same user drop the copy.
+    /// - Invalid and duplicated users are reported but kept.
+    #[test]
+    fn report_duplicates() {
+        let mut invalid = second_user();
+        invalid.email = String::from("jane");
+        let contents = format!(
+            "{{\"u\":{{{},{},{},{},{}}}}}",
+            record(0, &first_user()),
+            record(0, &invalid),
+            record(1, &first_user()),
+            record(1, &first_user()),
+            record(2, &second_user())
+        );
+        let diagnosis = Diagnosis::check(&contents);
+
+        assert_eq!(
+            diagnosis.problems,
+            vec![
+                Problem::DuplicateId {
+                    id: 1,
+                    new_id: None
+                },
+                Problem::DuplicateId {
+                    id: 0,
+                    new_id: Some(3)
+                },
+                Problem::DuplicateUser { id: 1, original: 0 },
+                Problem::InvalidUser {
+                    id: 3,
+                    error: ValidationError {
+                        field: Field::Email,
+                        reason: "must look like name@domain",
+                    },
+                },
+            ]
+        );
+        assert!(diagnosis.is_repairable());
+        assert_eq!(diagnosis.data.users().len(), 4);
+    }
+}
@@ -1,6 +1,21 @@
 pub mod command;
+mod config;
 mod data;
+mod diff;
+mod doctor;
+mod merge;
+mod patch;
+mod selection;
 mod user;
 
+pub use config::{
+    BASE_DN_VAR, CONFIG_VAR, Config, ConfigError, ConfigFile, DATA_VAR, DataFileError, FORMAT_VAR,
+    OutputFormat, POLICY_VAR, PROFILE_VAR,
+};
 pub use data::Data;
-pub use user::User;
+pub use diff::{Change, Diff, Modification};
+pub use doctor::{Diagnosis, Problem};
+pub use merge::{ConflictPolicy, MergeAction, MergeRecord};
+pub use patch::{Operation, Patch, PatchError};
+pub use selection::{FieldFilter, IdSelector, Selection, SelectionError};
+pub use user::{Field, User, ValidationError};
@@ -0,0 +1,339 @@
+use std::fmt::{self, Display, Formatter};
+
+use clap::ValueEnum;
+use serde::Serialize;
+
+use crate::{Data, User};
+
+/// How to resolve a conflict when merging another [`Data`] into the current
+/// one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ConflictPolicy {
+    /// Keep the current [`User`] when another one uses the same ID.
+    KeepOurs,
+
+    /// Replace the current [`User`] when another one uses the same ID.
+    TakeTheirs,
+
+    /// Keep the current [`User`] and add the other one with a new ID.
+    KeepBoth,
+
+    /// Match [`User`]s by email instead of ID, updating matches in place and
+    /// adding the rest with new IDs. [`User`]s without an email never match.
+    MatchByEmail,
+}
+
+/// What happened to a single [`User`] while merging.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(tag = "action", content = "id", rename_all = "snake_case")]
+pub enum MergeAction {
+    /// The [`User`] was added with the given ID.
+    Added(usize),
+
+    /// The [`User`] replaced the current one with the given ID.
+    Replaced(usize),
+
+    /// The [`User`] conflicted with the one with the given ID, which was kept.
+    Skipped(usize),
+
+    /// The [`User`] is identical to the one with the given ID.
+    Unchanged(usize),
+}
+
+/// The outcome of merging a single [`User`] from another [`Data`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct MergeRecord {
+    /// The ID of the [`User`] in the other [`Data`].
+    pub source_id: usize,
+
+    /// What happened to the [`User`].
+    #[serde(flatten)]
+    pub action: MergeAction,
+}
+
+impl Display for MergeRecord {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let source_id = self.source_id;
+
+        match self.action {
+            MergeAction::Added(id) => write!(f, "User {source_id}: added as user {id}."),
+            MergeAction::Replaced(id) => write!(f, "User {source_id}: replaced user {id}."),
+            MergeAction::Skipped(id) => {
+                write!(f, "User {source_id}: conflicts with user {id}, kept ours.")
+            }
+            MergeAction::Unchanged(id) => write!(f, "User {source_id}: identical to user {id}."),
+        }
+    }
+}
+
+/// Normalizes an email address so that matching ignores case and surrounding
+/// whitespace.
+fn normalize_email(email: &str) -> String {
+    email.trim().to_lowercase()
+}
+
+impl Data {
+    /// Merges the [`User`]s of another `Data` into this one.
+    ///
+    /// With every policy except [`ConflictPolicy::MatchByEmail`], [`User`]s
+    /// are matched by ID: [`User`]s whose ID is free are added with the same
+    /// ID, identical [`User`]s are left untouched, and differing [`User`]s are
+    /// resolved with the given `policy`. With [`ConflictPolicy::MatchByEmail`],
+    /// [`User`]s are matched by email address instead, matches are replaced in
+    /// place, and the rest, including those without an email, are added with
+    /// new IDs.
+    ///
+    /// Returns one [`MergeRecord`] per [`User`] in `other`, in ascending ID
+    /// order.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use user_registry_lib::{ConflictPolicy, Data, MergeAction, User};
+    /// let user = User {
+    ///     first_name: "John".to_string(),
+    ///     last_name: "Doe".to_string(),
+    ///     email: "john@example.com".to_string(),
+    ///     phone_number: "555-1234".to_string(),
+    /// };
+    ///
+    /// let mut data = Data::new();
+    /// let mut other = Data::new();
+    /// other.add_user(user.clone());
+    ///
+    /// let records = data.merge(&other, ConflictPolicy::KeepOurs);
+    /// assert_eq!(records[0].action, MergeAction::Added(0));
+    /// assert_eq!(data.user(0), Some(&user));
+    /// ```
+    pub fn merge(&mut self, other: &Data, policy: ConflictPolicy) -> Vec<MergeRecord> {
+        let mut records = Vec::new();
+        let mut conflicts = Vec::new();
+
+        for (source_id, user) in other.users() {
+            let action = if policy == ConflictPolicy::MatchByEmail {
+                self.merge_by_email(user)
+            } else {
+                match self.user(source_id) {
+                    None => {
+                        self.insert_user(source_id, user.clone());
+                        MergeAction::Added(source_id)
+                    }
+                    Some(current) if current == user => MergeAction::Unchanged(source_id),
+                    // Conflicts are resolved once every free ID has been
+                    // taken, so users added with a new ID never take the ID
+                    // of a later user from `other`.
+                    Some(_) => {
+                        conflicts.push((source_id, user));
+                        continue;
+                    }
+                }
+            };
+
+            records.push(MergeRecord { source_id, action });
+        }
+
+        for (source_id, user) in conflicts {
+            let action = match policy {
+                ConflictPolicy::KeepOurs => MergeAction::Skipped(source_id),
+                ConflictPolicy::TakeTheirs => {
+                    self.insert_user(source_id, user.clone());
+                    MergeAction::Replaced(source_id)
+                }
+                ConflictPolicy::KeepBoth | ConflictPolicy::MatchByEmail => {
+                    MergeAction::Added(self.add_user(user.clone()))
+                }
+            };
+
+            records.push(MergeRecord { source_id, action });
+        }
+
+        records.sort_by_key(|record| record.source_id);
+        records
+    }
+
+    /// Merges a single [`User`] by matching their email address.
+    fn merge_by_email(&mut self, user: &User) -> MergeAction {
+        let email = normalize_email(&user.email);
+        // Blank emails would all match each other.
+        let matching = self
+            .users()
+            .into_iter()
+            .find(|(_, current)| !email.is_empty() && normalize_email(&current.email) == email)
+            .map(|(id, current)| (id, current == user));
+
+        match matching {
+            Some((id, true)) => MergeAction::Unchanged(id),
+            Some((id, false)) => {
+                self.insert_user(id, user.clone());
+                MergeAction::Replaced(id)
+            }
+            None => MergeAction::Added(self.add_user(user.clone())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Helper function to create a [`User`] with data for testing purposes.
+    fn first_user() -> User {
+        User {
+            first_name: String::from("firstName"),
+            last_name: String::from("firstSurname"),
+            email: String::from("firstEmail"),
+            phone_number: String::from("0123456789"),
+        }
+    }
+
+    /// Helper function to create a [`User`] with data for testing purposes.
+    fn second_user() -> User {
+        User {
+            first_name: String::from("secondName"),
+            last_name: String::from("secondSurname"),
+            email: String::from("secondEmail"),
+            phone_number: String::from("9786543210"),
+        }
+    }
+
+    /// Helper function to create a [`User`] with data for testing purposes.
+    fn third_user() -> User {
+        User {
+            first_name: String::from("thirdName"),
+            last_name: String::from("thirdSurname"),
+            email: String::from("thirdEmail"),
+            phone_number: String::from("5555555555"),
+        }
+    }
+
+    /// Helper function to create the current and other [`Data`] used by the
+    /// tests.
+    ///
+    /// The current [`Data`] has the first and second [`User`]s. The other one
+    /// has the first [`User`] with the same ID, the third [`User`] in the ID of
+    /// the second one, and the second [`User`] in a free ID.
+    fn data() -> (Data, Data) {
+        let mut ours = Data::new();
+        ours.add_user(first_user());
+        ours.add_user(second_user());
+
+        let mut theirs = Data::new();
+        theirs.add_user(first_user());
+        theirs.add_user(third_user());
+        theirs.add_user(second_user());
+
+        (ours, theirs)
+    }
+
+    /// Tests merging with the [`ConflictPolicy::KeepOurs`] policy.
+    #[test]
+    fn merge_keep_ours() {
+        let (mut ours, theirs) = data();
+        let records = ours.merge(&theirs, ConflictPolicy::KeepOurs);
+
+        let actions: Vec<_> = records.iter().map(|record| record.action).collect();
+        assert_eq!(
+            actions,
+            [
+                MergeAction::Unchanged(0),
+                MergeAction::Skipped(1),
+                MergeAction::Added(2)
+            ]
+        );
+        assert_eq!(ours.user(1), Some(&second_user()));
+    }
+
+    /// Tests merging with the [`ConflictPolicy::TakeTheirs`] policy.
+    #[test]
+    fn merge_take_theirs() {
+        let (mut ours, theirs) = data();
+        let records = ours.merge(&theirs, ConflictPolicy::TakeTheirs);
+
+        assert_eq!(records[1].action, MergeAction::Replaced(1));
+        assert_eq!(ours.user(1), Some(&third_user()));
+        assert_eq!(ours.users().len(), 3);
+    }
+
+    /// Tests merging with the [`ConflictPolicy::KeepBoth`] policy.
+    ///
+    /// This test ensures that conflicting [`User`]s get a new ID that does not
+    /// collide with a later [`User`] from the other [`Data`].
+    #[test]
+    fn merge_keep_both() {
+        let (mut ours, theirs) = data();
+        let records = ours.merge(&theirs, ConflictPolicy::KeepBoth);
+
+        let actions: Vec<_> = records.iter().map(|record| record.action).collect();
+        assert_eq!(
+            actions,
+            [
+                MergeAction::Unchanged(0),
+                MergeAction::Added(3),
+                MergeAction::Added(2)
+            ]
+        );
+        assert_eq!(ours.user(1), Some(&second_user()));
+        assert_eq!(ours.user(3), Some(&third_user()));
+    }
+
+    /// Tests merging with the [`ConflictPolicy::MatchByEmail`] policy.
+    ///
+    /// This test ensures that:
+    /// - [`User`]s are matched by email regardless of case.
+    /// - Matching [`User`]s are replaced in place.
+    /// - Other [`User`]s are added with new IDs.
+    #[test]
+    fn merge_match_by_email() {
+        let (mut ours, _) = data();
+
+        let mut changed = second_user();
+        changed.email = String::from("SECONDEMAIL");
+        changed.phone_number = String::from("0000000000");
+
+        let mut theirs = Data::new();
+        theirs.add_user(changed.clone());
+        theirs.add_user(third_user());
+
+        let records = ours.merge(&theirs, ConflictPolicy::MatchByEmail);
+
+        assert_eq!(
+            records,
+            [
+                MergeRecord {
+                    source_id: 0,
+                    action: MergeAction::Replaced(1)
+                },
+                MergeRecord {
+                    source_id: 1,
+                    action: MergeAction::Added(2)
+                },
+            ]
+        );
+        assert_eq!(ours.user(1), Some(&changed));
+    }
+
+    /// Tests merging [`User`]s without an email with the
+    /// [`ConflictPolicy::MatchByEmail`] policy.
+    ///
+    /// This test ensures that:
+    /// - [`User`]s with an empty or blank email don't match each other.
+    /// - Such [`User`]s are added with new IDs instead of replacing others.
+    #[test]
+    fn merge_match_by_blank_email() {
+        let mut ours = Data::new();
+        let mut first = first_user();
+        first.email = String::new();
+        ours.add_user(first.clone());
+
+        let mut second = second_user();
+        second.email = String::from("  ");
+        let mut theirs = Data::new();
+        theirs.add_user(second.clone());
+
+        let records = ours.merge(&theirs, ConflictPolicy::MatchByEmail);
+
+        assert_eq!(records[0].action, MergeAction::Added(1));
+        assert_eq!(ours.user(0), Some(&first));
+        assert_eq!(ours.user(1), Some(&second));
+    }
+}
@@ -0,0 +1,533 @@
+use std::{
+    error,
+    fmt::{self, Display, Formatter},
+};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::{Data, Diff, Field, User};
+
+/// A single JSON Patch operation, as defined by RFC 6902.
+///
+/// Paths are JSON Pointers (RFC 6901) into the public JSON representation of
+/// a [`Data`], as returned by [`Data::to_json`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+pub enum Operation {
+    Add { path: String, value: Value },
+    Remove { path: String },
+    Replace { path: String, value: Value },
+    Move { from: String, path: String },
+    Copy { from: String, path: String },
+    Test { path: String, value: Value },
+}
+
+/// A JSON Patch document: a list of [`Operation`]s applied in order.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Patch(pub Vec<Operation>);
+
+/// An error produced while applying a [`Patch`].
+///
+/// Every variant except [`PatchError::InvalidData`] carries the index of the
+/// failing [`Operation`] and the offending JSON Pointer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PatchError {
+    InvalidPointer { index: usize, pointer: String },
+    PathNotFound { index: usize, pointer: String },
+    TestFailed { index: usize, pointer: String },
+    MoveIntoChild { index: usize, pointer: String },
+    InvalidData(String),
+}
+
+impl Display for PatchError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidPointer { index, pointer } => {
+                write!(
+                    f,
+                    "Operation {index}: '{pointer}' is not a valid JSON Pointer."
+                )
+            }
+            Self::PathNotFound { index, pointer } => {
+                write!(f, "Operation {index}: the path '{pointer}' does not exist.")
+            }
+            Self::TestFailed { index, pointer } => {
+                write!(
+                    f,
+                    "Operation {index}: the value at '{pointer}' does not match."
+                )
+            }
+            Self::MoveIntoChild { index, pointer } => write!(
+                f,
+                "Operation {index}: '{pointer}' cannot be moved into one of its children."
+            ),
+            Self::InvalidData(reason) => {
+                write!(f, "The patched document is not a valid registry: {reason}")
+            }
+        }
+    }
+}
+
+impl error::Error for PatchError {}
+
+/// Splits a JSON Pointer into its unescaped reference tokens.
+fn parse_pointer(pointer: &str) -> Option<Vec<String>> {
+    if pointer.is_empty() {
+        return Some(Vec::new());
+    }
+
+    pointer
+        .strip_prefix('/')?
+        .split('/')
+        .map(|token| {
+            let mut unescaped = String::with_capacity(token.len());
+            let mut chars = token.chars();
+
+            while let Some(c) = chars.next() {
+                match c {
+                    '~' => match chars.next() {
+                        Some('0') => unescaped.push('~'),
+                        Some('1') => unescaped.push('/'),
+                        _ => return None,
+                    },
+                    c => unescaped.push(c),
+                }
+            }
+
+            Some(unescaped)
+        })
+        .collect()
+}
+
+/// Parses an array index token, which must not have leading zeros.
+fn parse_index(token: &str) -> Option<usize> {
+    if token.len() > 1 && token.starts_with('0') {
+        return None;
+    }
+
+    token.parse().ok()
+}
+
+/// Returns the value a JSON Pointer refers to.
+fn resolve<'a>(document: &'a mut Value, tokens: &[String]) -> Option<&'a mut Value> {
+    tokens
+        .iter()
+        .try_fold(document, |value, token| match value {
+            Value::Object(map) => map.get_mut(token),
+            Value::Array(array) => array.get_mut(parse_index(token)?),
+            _ => None,
+        })
+}
+
+/// Adds a value at the location a JSON Pointer refers to, replacing any
+/// existing object member or inserting into an array.
+fn add(document: &mut Value, tokens: &[String], value: Value) -> Option<()> {
+    let Some((last, parent)) = tokens.split_last() else {
+        *document = value;
+        return Some(());
+    };
+
+    match resolve(document, parent)? {
+        Value::Object(map) => {
+            map.insert(last.clone(), value);
+        }
+        Value::Array(array) if last == "-" => array.push(value),
+        Value::Array(array) => {
+            let index = parse_index(last).filter(|index| *index <= array.len())?;
+            array.insert(index, value);
+        }
+        _ => return None,
+    }
+
+    Some(())
+}
+
+/// Removes and returns the value a JSON Pointer refers to.
+fn remove(document: &mut Value, tokens: &[String]) -> Option<Value> {
+    let (last, parent) = tokens.split_last()?;
+
+    match resolve(document, parent)? {
+        Value::Object(map) => map.remove(last),
+        Value::Array(array) => {
+            let index = parse_index(last).filter(|index| *index < array.len())?;
+            Some(array.remove(index))
+        }
+        _ => None,
+    }
+}
+
+/// Applies a single [`Operation`] to a JSON document.
+fn apply_operation(
+    document: &mut Value,
+    index: usize,
+    operation: &Operation,
+) -> Result<(), PatchError> {
+    let tokens = |pointer: &str| {
+        parse_pointer(pointer).ok_or_else(|| PatchError::InvalidPointer {
+            index,
+            pointer: pointer.to_string(),
+        })
+    };
+    let not_found = |pointer: &str| PatchError::PathNotFound {
+        index,
+        pointer: pointer.to_string(),
+    };
+
+    match operation {
+        Operation::Add { path, value } => {
+            add(document, &tokens(path)?, value.clone()).ok_or_else(|| not_found(path))
+        }
+        Operation::Remove { path } => remove(document, &tokens(path)?)
+            .map(|_| ())
+            .ok_or_else(|| not_found(path)),
+        Operation::Replace { path, value } => {
+            let target = resolve(document, &tokens(path)?).ok_or_else(|| not_found(path))?;
+            *target = value.clone();
+            Ok(())
+        }
+        Operation::Move { from, path } => {
+            let from_tokens = tokens(from)?;
+            let path_tokens = tokens(path)?;
+
+            if path_tokens.len() > from_tokens.len() && path_tokens.starts_with(&from_tokens) {
+                return Err(PatchError::MoveIntoChild {
+                    index,
+                    pointer: from.clone(),
+                });
+            }
+
+            let value = remove(document, &from_tokens).ok_or_else(|| not_found(from))?;
+            add(document, &path_tokens, value).ok_or_else(|| not_found(path))
+        }
+        Operation::Copy { from, path } => {
+            let value = resolve(document, &tokens(from)?)
+                .ok_or_else(|| not_found(from))?
+                .clone();
+            add(document, &tokens(path)?, value).ok_or_else(|| not_found(path))
+        }
+        Operation::Test { path, value } => {
+            let target = resolve(document, &tokens(path)?).ok_or_else(|| not_found(path))?;
+
+            if target == value {
+                Ok(())
+            } else {
+                Err(PatchError::TestFailed {
+                    index,
+                    pointer: path.clone(),
+                })
+            }
+        }
+    }
+}
+
+/// Returns the public JSON representation of a [`User`].
+fn user_to_json(user: &User) -> Value {
+    Value::Object(
+        Field::ALL
+            .into_iter()
+            .map(|field| (field.name().to_string(), field.value(user).into()))
+            .collect(),
+    )
+}
+
+/// Builds a [`User`] from their public JSON representation.
+fn user_from_json(id: &str, value: &Value) -> Result<User, String> {
+    let Value::Object(map) = value else {
+        return Err(format!("user {id} must be an object"));
+    };
+
+    if let Some(key) = map.keys().find(|key| Field::from_name(key).is_none()) {
+        return Err(format!("user {id} has an unknown field '{key}'"));
+    }
+
+    let mut user = User {
+        first_name: String::new(),
+        last_name: String::new(),
+        email: String::new(),
+        phone_number: String::new(),
+    };
+
+    for field in Field::ALL {
+        match map.get(field.name()) {
+            Some(Value::String(value)) => *field.value_mut(&mut user) = value.clone(),
+            Some(_) => {
+                return Err(format!(
+                    "the field '{}' of user {id} must be a string",
+                    field.name()
+                ));
+            }
+            None => return Err(format!("user {id} is missing the field '{}'", field.name())),
+        }
+    }
+
+    Ok(user)
+}
+
+impl Data {
+    /// Returns the public JSON representation of the `Data`.
+    ///
+    /// Unlike the compact format used in the data file, this representation
+    /// uses full field names: `{"users": {"<id>": {"first_name": ...,
+    /// "last_name": ..., "email": ..., "phone_number": ...}}}`. It is the
+    /// document that [`Patch`]es are applied to.
+    pub fn to_json(&self) -> Value {
+        let users: Map<_, _> = self
+            .users()
+            .into_iter()
+            .map(|(id, user)| (id.to_string(), user_to_json(user)))
+            .collect();
+
+        serde_json::json!({ "users": users })
+    }
+
+    /// Builds a `Data` from its public JSON representation, as returned by
+    /// [`Data::to_json`].
+    ///
+    /// # Errors
+    /// This function returns [`PatchError::InvalidData`] if the document does
+    /// not follow the public JSON representation.
+    pub fn from_json(document: &Value) -> Result<Self, PatchError> {
+        let invalid = |reason: &str| PatchError::InvalidData(reason.to_string());
+
+        let Value::Object(root) = document else {
+            return Err(invalid("the document must be an object"));
+        };
+
+        if let Some(key) = root.keys().find(|key| *key != "users") {
+            return Err(PatchError::InvalidData(format!("unknown member '{key}'")));
+        }
+
+        let Some(Value::Object(users)) = root.get("users") else {
+            return Err(invalid("'users' must be an object"));
+        };
+
+        let mut data = Data::new();
+
+        for (id, user) in users {
+            let user = user_from_json(id, user).map_err(PatchError::InvalidData)?;
+            let id = parse_index(id)
+                .ok_or_else(|| PatchError::InvalidData(format!("'{id}' is not a valid ID")))?;
+
+            data.insert_user(id, user);
+        }
+
+        Ok(data)
+    }
+
+    /// Applies a JSON [`Patch`] to the `Data`.
+    ///
+    /// The [`Patch`] is applied to the public JSON representation returned by
+    /// [`Data::to_json`]. Patches are atomic: if any [`Operation`] fails, or if
+    /// the patched document is not a valid registry, the `Data` is left
+    /// unchanged.
+    ///
+    /// # Errors
+    /// This function returns a [`PatchError`] describing the first failing
+    /// [`Operation`], or why the patched document is invalid.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use user_registry_lib::{Data, Patch, User};
+    /// let mut data = Data::new();
+    /// data.add_user(User {
+    ///     first_name: "John".to_string(),
+    ///     last_name: "Doe".to_string(),
+    ///     email: "john@example.com".to_string(),
+    ///     phone_number: "555-1234".to_string(),
+    /// });
+    ///
+    /// let patch: Patch = serde_json::from_str(
+    ///     r#"[{ "op": "replace", "path": "/users/0/email", "value": "jd@example.com" }]"#,
+    /// )
+    /// .unwrap();
+    ///
+    /// data.apply_patch(&patch).unwrap();
+    /// assert_eq!(data.user(0).unwrap().email, "jd@example.com");
+    /// ```
+    pub fn apply_patch(&mut self, patch: &Patch) -> Result<(), PatchError> {
+        let mut document = self.to_json();
+
+        for (index, operation) in patch.0.iter().enumerate() {
+            apply_operation(&mut document, index, operation)?;
+        }
+
+        *self = Self::from_json(&document)?;
+        Ok(())
+    }
+}
+
+impl Diff {
+    /// Returns a JSON [`Patch`] that turns the old [`Data`] into the new one.
+    ///
+    /// Every removal and field replacement is preceded by a `test` operation
+    /// on the old value, so the [`Patch`] fails instead of silently
+    /// overwriting a registry that changed in the meantime.
+    pub fn to_patch(&self) -> Patch {
+        let mut operations = Vec::new();
+        let user_path = |id: usize| format!("/users/{id}");
+
+        for (id, user) in &self.removed {
+            operations.push(Operation::Test {
+                path: user_path(*id),
+                value: user_to_json(user),
+            });
+            operations.push(Operation::Remove {
+                path: user_path(*id),
+            });
+        }
+
+        for modification in &self.modified {
+            for change in &modification.changes {
+                let path = format!("{}/{}", user_path(modification.id), change.field.name());
+
+                operations.push(Operation::Test {
+                    path: path.clone(),
+                    value: change.old.clone().into(),
+                });
+                operations.push(Operation::Replace {
+                    path,
+                    value: change.new.clone().into(),
+                });
+            }
+        }
+
+        for (id, user) in &self.added {
+            operations.push(Operation::Add {
+                path: user_path(*id),
+                value: user_to_json(user),
+            });
+        }
+
+        Patch(operations)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    /// Helper function to create a [`User`] with data for testing purposes.
+    fn first_user() -> User {
+        User {
+            first_name: String::from("firstName"),
+            last_name: String::from("firstSurname"),
+            email: String::from("firstEmail"),
+            phone_number: String::from("0123456789"),
+        }
+    }
+
+    /// Helper function to create a [`User`] with data for testing purposes.
+    fn second_user() -> User {
+        User {
+            first_name: String::from("secondName"),
+            last_name: String::from("secondSurname"),
+            email: String::from("secondEmail"),
+            phone_number: String::from("9786543210"),
+        }
+    }
+
+    /// Helper function to parse a [`Patch`] from a JSON value.
+    fn patch(value: Value) -> Patch {
+        serde_json::from_value(value).unwrap()
+    }
+
+    /// Tests converting a [`Data`] to and from its public JSON representation.
+    #[test]
+    fn json_round_trip() {
+        let mut data = Data::new();
+        data.add_user(first_user());
+        data.insert_user(5, second_user());
+
+        let json = data.to_json();
+        assert_eq!(json["users"]["5"]["first_name"], "secondName");
+        assert_eq!(Data::from_json(&json).unwrap().users(), data.users());
+    }
+
+    /// Tests applying a [`Patch`] with every kind of [`Operation`].
+    #[test]
+    fn apply_all_operations() {
+        let mut data = Data::new();
+        data.add_user(first_user());
+
+        data.apply_patch(&patch(json!([
+            { "op": "test", "path": "/users/0/email", "value": "firstEmail" },
+            { "op": "copy", "from": "/users/0", "path": "/users/1" },
+            { "op": "replace", "path": "/users/1/first_name", "value": "secondName" },
+            { "op": "move", "from": "/users/0", "path": "/users/4" },
+            { "op": "add", "path": "/users/2", "value": {
+                "first_name": "a", "last_name": "b", "email": "c", "phone_number": "d"
+            } },
+            { "op": "remove", "path": "/users/2" },
+        ])))
+        .unwrap();
+
+        let mut renamed = first_user();
+        renamed.first_name = String::from("secondName");
+
+        assert_eq!(data.users(), [(1, &renamed), (4, &first_user())]);
+    }
+
+    /// Tests that failing [`Patch`]es leave the [`Data`] unchanged.
+    ///
+    /// This test ensures that:
+    /// - A failed `test` operation aborts the whole [`Patch`].
+    /// - A missing path is reported with the index of the [`Operation`].
+    /// - A [`Patch`] producing an invalid registry is rejected.
+    #[test]
+    fn failed_patches_are_atomic() {
+        let mut data = Data::new();
+        data.add_user(first_user());
+
+        let result = data.apply_patch(&patch(json!([
+            { "op": "remove", "path": "/users/0" },
+            { "op": "test", "path": "/users/0", "value": null },
+        ])));
+        assert_eq!(
+            result,
+            Err(PatchError::PathNotFound {
+                index: 1,
+                pointer: String::from("/users/0")
+            })
+        );
+
+        let result = data.apply_patch(&patch(json!([
+            { "op": "replace", "path": "/users/0/email", "value": 5 },
+        ])));
+        assert!(matches!(result, Err(PatchError::InvalidData(_))));
+
+        let result = data.apply_patch(&patch(json!([
+            { "op": "test", "path": "/users/0/email", "value": "otherEmail" },
+        ])));
+        assert!(matches!(
+            result,
+            Err(PatchError::TestFailed { index: 0, .. })
+        ));
+
+        assert_eq!(data.users(), [(0, &first_user())]);
+    }
+
+    /// Tests that the [`Patch`] generated from a [`Diff`] turns the old
+    /// [`Data`] into the new one.
+    #[test]
+    fn diff_to_patch() {
+        let mut old = Data::new();
+        old.add_user(first_user());
+        old.add_user(second_user());
+
+        let mut changed = first_user();
+        changed.email = String::from("newEmail");
+
+        let mut new = Data::new();
+        new.add_user(changed);
+        new.insert_user(3, second_user());
+
+        let patch = Diff::new(&old, &new).to_patch();
+        old.apply_patch(&patch).unwrap();
+
+        assert_eq!(old.users(), new.users());
+    }
+}
@@ -0,0 +1,357 @@
+use std::{
+    error,
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+
+use crate::{Data, Field, User};
+
+/// An error produced while parsing an [`IdSelector`] or a [`FieldFilter`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SelectionError {
+    /// The text is not an ID or a range of IDs.
+    InvalidId(String),
+
+    /// The range of IDs ends before it starts.
+    EmptyRange(String),
+
+    /// The filter is not written as `field=pattern`.
+    InvalidFilter(String),
+
+    /// The filter names a field that doesn't exist.
+    UnknownField(String),
+}
+
+impl Display for SelectionError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidId(text) => {
+                write!(f, "'{text}' is not an ID or a range of IDs like '3-7'.")
+            }
+            Self::EmptyRange(text) => write!(f, "The range '{text}' ends before it starts."),
+            Self::InvalidFilter(text) => {
+                write!(f, "'{text}' is not a filter like 'email=*@example.com'.")
+            }
+            Self::UnknownField(name) => write!(
+                f,
+                "Unknown field '{name}'. Use first_name, last_name, email or phone_number."
+            ),
+        }
+    }
+}
+
+impl error::Error for SelectionError {}
+
+/// Selects [`User`]s by ID: a single ID like `3` or an inclusive range like
+/// `3-7`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IdSelector {
+    Single(usize),
+    Range(usize, usize),
+}
+
+impl IdSelector {
+    /// Returns whether `id` is selected.
+    pub fn contains(self, id: usize) -> bool {
+        match self {
+            Self::Single(single) => id == single,
+            Self::Range(start, end) => (start..=end).contains(&id),
+        }
+    }
+}
+
+impl FromStr for IdSelector {
+    type Err = SelectionError;
+
+    /// Parses an ID or a range of IDs.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use user_registry_lib::IdSelector;
+    ///
+    /// assert_eq!("3".parse(), Ok(IdSelector::Single(3)));
+    /// assert_eq!("3-7".parse(), Ok(IdSelector::Range(3, 7)));
+    /// assert!("7-3".parse::<IdSelector>().is_err());
+    /// ```
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let parse = |id: &str| {
+            id.trim()
+                .parse()
+                .map_err(|_| SelectionError::InvalidId(text.to_string()))
+        };
+
+        let Some((start, end)) = text.split_once('-') else {
+            return parse(text).map(Self::Single);
+        };
+
+        let (start, end) = (parse(start)?, parse(end)?);
+        if end < start {
+            return Err(SelectionError::EmptyRange(text.to_string()));
+        }
+
+        Ok(Self::Range(start, end))
+    }
+}
+
+/// Selects [`User`]s whose [`Field`] matches a pattern, written as
+/// `field=pattern`.
+///
+/// Patterns are matched case-insensitively against the whole value, and `*`
+/// matches any text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldFilter {
+    pub field: Field,
+    pub pattern: String,
+}
+
+/// Returns whether `value` matches the `pattern`, where `*` matches any text.
+fn matches_pattern(pattern: &str, value: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+
+    let Some(mut rest) = value.strip_prefix(first) else {
+        return false;
+    };
+    let mut parts: Vec<&str> = parts.collect();
+
+    let Some(last) = parts.pop() else {
+        // There is no wildcard, so the whole value must be matched.
+        return rest.is_empty();
+    };
+
+    for part in parts {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+
+    rest.ends_with(last)
+}
+
+impl FieldFilter {
+    /// Returns whether the [`User`] matches this filter.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use user_registry_lib::{FieldFilter, User};
+    ///
+    /// let user = User {
+    ///     first_name: String::from("Ann"),
+    ///     last_name: String::from("Lee"),
+    ///     email: String::from("ann@Example.com"),
+    ///     phone_number: String::from("555"),
+    /// };
+    ///
+    /// let filter: FieldFilter = "email=*@example.com".parse().unwrap();
+    /// assert!(filter.matches(&user));
+    /// ```
+    pub fn matches(&self, user: &User) -> bool {
+        matches_pattern(
+            &self.pattern.to_lowercase(),
+            &self.field.value(user).to_lowercase(),
+        )
+    }
+}
+
+impl FromStr for FieldFilter {
+    type Err = SelectionError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let (name, pattern) = text
+            .split_once('=')
+            .ok_or_else(|| SelectionError::InvalidFilter(text.to_string()))?;
+        let name = name.trim();
+        let field =
+            Field::from_name(name).ok_or_else(|| SelectionError::UnknownField(name.to_string()))?;
+
+        Ok(Self {
+            field,
+            pattern: pattern.to_string(),
+        })
+    }
+}
+
+/// Selects [`User`]s by ID and by field.
+///
+/// A [`User`] is selected if its ID matches one of the `ids`, or if there are
+/// none, and if it matches every filter. An empty selection selects nothing.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Selection {
+    pub ids: Vec<IdSelector>,
+    pub filters: Vec<FieldFilter>,
+}
+
+impl Selection {
+    /// Returns whether the selection has neither IDs nor filters.
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty() && self.filters.is_empty()
+    }
+
+    /// Returns whether the [`User`] with the given ID is selected.
+    pub fn matches(&self, id: usize, user: &User) -> bool {
+        !self.is_empty()
+            && (self.ids.is_empty() || self.ids.iter().any(|ids| ids.contains(id)))
+            && self.filters.iter().all(|filter| filter.matches(user))
+    }
+}
+
+impl Data {
+    /// Returns the IDs of the selected [`User`]s, in ascending order.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use user_registry_lib::{Data, Selection, User};
+    ///
+    /// let mut data = Data::default();
+    /// for email in ["ann@staff.com", "bob@contractor.com", "eve@contractor.com"] {
+    ///     data.add_user(User {
+    ///         first_name: String::from("First"),
+    ///         last_name: String::from("Last"),
+    ///         email: email.to_string(),
+    ///         phone_number: String::from("555"),
+    ///     });
+    /// }
+    ///
+    /// let selection = Selection {
+    ///     ids: vec!["0-1".parse().unwrap()],
+    ///     filters: vec!["email=*@contractor.com".parse().unwrap()],
+    /// };
+    /// assert_eq!(data.select(&selection), vec![1]);
+    /// ```
+    pub fn select(&self, selection: &Selection) -> Vec<usize> {
+        self.users()
+            .into_iter()
+            .filter(|(id, user)| selection.matches(*id, user))
+            .map(|(id, _)| id)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Helper function to create a [`User`] with data for testing purposes.
+    fn first_user() -> User {
+        User {
+            first_name: String::from("John"),
+            last_name: String::from("Doe"),
+            email: String::from("john.doe@staff.example.com"),
+            phone_number: String::from("+1 555 0100"),
+        }
+    }
+
+    /// Helper function to create a [`User`] with data for testing purposes.
+    fn second_user() -> User {
+        User {
+            first_name: String::from("Jane"),
+            last_name: String::from("Smith"),
+            email: String::from("jane.smith@contractor.example.com"),
+            phone_number: String::from("+1 555 0101"),
+        }
+    }
+
+    /// Tests parsing [`IdSelector`]s.
+    ///
+    /// This test ensures that:
+    /// - Single IDs and ranges are parsed, with spaces around range bounds.
+    /// - Ranges that end before they start are rejected.
+    /// - Text that isn't an ID is rejected.
+    #[test]
+    fn parse_id_selectors() {
+        assert_eq!("7".parse(), Ok(IdSelector::Single(7)));
+        assert_eq!("2 - 4".parse(), Ok(IdSelector::Range(2, 4)));
+        assert_eq!("4-4".parse(), Ok(IdSelector::Range(4, 4)));
+        assert_eq!(
+            "4-2".parse::<IdSelector>(),
+            Err(SelectionError::EmptyRange(String::from("4-2")))
+        );
+        assert_eq!(
+            "a-2".parse::<IdSelector>(),
+            Err(SelectionError::InvalidId(String::from("a-2")))
+        );
+        assert!("-2".parse::<IdSelector>().is_err());
+        assert!(IdSelector::Range(2, 4).contains(4));
+        assert!(!IdSelector::Range(2, 4).contains(5));
+    }
+
+    /// Tests parsing [`FieldFilter`]s.
+    ///
+    /// This test ensures that:
+    /// - Filters are parsed as `field=pattern`.
+    /// - Unknown fields and missing `=` are rejected.
+    #[test]
+    fn parse_field_filters() {
+        assert_eq!(
+            "last_name=Do*".parse(),
+            Ok(FieldFilter {
+                field: Field::LastName,
+                pattern: String::from("Do*"),
+            })
+        );
+        assert_eq!(
+            "name=John".parse::<FieldFilter>(),
+            Err(SelectionError::UnknownField(String::from("name")))
+        );
+        assert_eq!(
+            "John".parse::<FieldFilter>(),
+            Err(SelectionError::InvalidFilter(String::from("John")))
+        );
+    }
+
+    /// Tests matching values against the patterns of filters.
+    ///
+    /// This test ensures that:
+    /// - Patterns without wildcards match the whole value only.
+    /// - Wildcards match any text, including none, at any position.
+    /// - Matching ignores case.
+    #[test]
+    fn match_patterns() {
+        let user = first_user();
+        let matches = |filter: &str| filter.parse::<FieldFilter>().unwrap().matches(&user);
+
+        assert!(matches("first_name=john"));
+        assert!(!matches("first_name=joh"));
+        assert!(matches("first_name=joh*"));
+        assert!(matches("email=*@staff.example.com"));
+        assert!(matches("email=john*staff*"));
+        assert!(matches("email=*"));
+        assert!(!matches("email=*@contractor.example.com"));
+        assert!(matches("phone_number=+1 555 01*0"));
+        assert!(!matches("phone_number=*0101"));
+    }
+
+    /// Tests selecting [`User`]s with a [`Selection`].
+    ///
+    /// This test ensures that:
+    /// - IDs and ranges select the union of their users.
+    /// - Filters narrow the selection down, all of them applying.
+    /// - An empty selection selects nothing.
+    #[test]
+    fn select_users() {
+        let mut data = Data::default();
+        data.add_user(first_user());
+        data.add_user(second_user());
+        data.add_user(first_user());
+
+        let select = |ids: &[&str], filters: &[&str]| {
+            data.select(&Selection {
+                ids: ids.iter().map(|id| id.parse().unwrap()).collect(),
+                filters: filters
+                    .iter()
+                    .map(|filter| filter.parse().unwrap())
+                    .collect(),
+            })
+        };
+
+        assert_eq!(select(&["0", "2-5"], &[]), vec![0, 2]);
+        assert_eq!(select(&[], &["first_name=john"]), vec![0, 2]);
+        assert_eq!(
+            select(&[], &["first_name=j*", "email=*contractor*"]),
+            vec![1]
+        );
+        assert_eq!(select(&["1-2"], &["last_name=doe"]), vec![2]);
+        assert_eq!(select(&[], &[]), Vec::<usize>::new());
+    }
+}
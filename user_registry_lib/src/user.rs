@@ -1,3 +1,8 @@
+use std::{
+    error,
+    fmt::{self, Display, Formatter},
+};
+
 use serde::{Deserialize, Serialize};
 
 /// Represents a user with basic contact information.
@@ -22,3 +27,224 @@ pub struct User {
     #[serde(rename = "p")]
     pub phone_number: String,
 }
+
+impl User {
+    /// Checks that every field of the `User` holds a valid value.
+    ///
+    /// # Errors
+    /// This function returns a [`ValidationError`] for the first invalid field,
+    /// as checked by [`Field::validate`].
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        Field::ALL
+            .into_iter()
+            .try_for_each(|field| field.validate(field.value(self)))
+    }
+
+    /// Returns whether any field of the `User` contains the given `query`,
+    /// ignoring case.
+    ///
+    /// An empty `query` matches every `User`.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use user_registry_lib::User;
+    /// let user = User {
+    ///     first_name: "John".to_string(),
+    ///     last_name: "Doe".to_string(),
+    ///     email: "john@example.com".to_string(),
+    ///     phone_number: "555-1234".to_string(),
+    /// };
+    ///
+    /// assert!(user.matches("doe"));
+    /// assert!(user.matches("555"));
+    /// assert!(!user.matches("jane"));
+    /// ```
+    pub fn matches(&self, query: &str) -> bool {
+        let query = query.to_lowercase();
+
+        Field::ALL
+            .into_iter()
+            .any(|field| field.value(self).to_lowercase().contains(&query))
+    }
+}
+
+/// Identifies one of the fields of a [`User`].
+///
+/// This is used wherever a field has to be referred to by name, such as when
+/// reporting which fields changed between two versions of a [`User`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Field {
+    FirstName,
+    LastName,
+    Email,
+    PhoneNumber,
+}
+
+impl Field {
+    /// Every [`User`] field, in declaration order.
+    pub const ALL: [Self; 4] = [
+        Self::FirstName,
+        Self::LastName,
+        Self::Email,
+        Self::PhoneNumber,
+    ];
+
+    /// Returns the machine-readable name of the field, as used in JSON.
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::FirstName => "first_name",
+            Self::LastName => "last_name",
+            Self::Email => "email",
+            Self::PhoneNumber => "phone_number",
+        }
+    }
+
+    /// Returns the field identified by its machine-readable name.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|field| field.name() == name)
+    }
+
+    /// Returns the value of this field for the given [`User`].
+    pub fn value(self, user: &User) -> &str {
+        match self {
+            Self::FirstName => &user.first_name,
+            Self::LastName => &user.last_name,
+            Self::Email => &user.email,
+            Self::PhoneNumber => &user.phone_number,
+        }
+    }
+
+    /// Checks that a value is valid for this field.
+    ///
+    /// Names must not be blank. Email addresses must have a single `@` with
+    /// text on both sides and no whitespace. Phone numbers must contain at
+    /// least one digit and only digits, spaces, `-`, `(`, `)`, `.`, or a
+    /// leading `+`.
+    ///
+    /// # Errors
+    /// This function returns a [`ValidationError`] describing why the value
+    /// is invalid.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use user_registry_lib::Field;
+    /// assert!(Field::Email.validate("john@example.com").is_ok());
+    /// assert!(Field::Email.validate("john").is_err());
+    /// assert!(Field::PhoneNumber.validate("+1 (555) 123-4567").is_ok());
+    /// ```
+    pub fn validate(self, value: &str) -> Result<(), ValidationError> {
+        let error = |reason: &'static str| {
+            Err(ValidationError {
+                field: self,
+                reason,
+            })
+        };
+
+        match self {
+            Self::FirstName | Self::LastName if value.trim().is_empty() => {
+                error("must not be empty")
+            }
+            Self::FirstName | Self::LastName => Ok(()),
+            Self::Email => match value.split_once('@') {
+                _ if value.contains(char::is_whitespace) => error("must not contain spaces"),
+                Some((local, domain)) if !local.is_empty() && !domain.is_empty() => {
+                    if domain.contains('@') {
+                        error("must contain a single '@'")
+                    } else {
+                        Ok(())
+                    }
+                }
+                _ => error("must look like name@domain"),
+            },
+            Self::PhoneNumber => {
+                let digits = value.strip_prefix('+').unwrap_or(value);
+
+                if !digits.chars().any(|c| c.is_ascii_digit()) {
+                    error("must contain digits")
+                } else if !digits
+                    .chars()
+                    .all(|c| c.is_ascii_digit() || " -().".contains(c))
+                {
+                    error("may only contain digits, spaces, '-', '(', ')', '.' and a leading '+'")
+                } else {
+                    Ok(())
+                }
+            }
+        }
+    }
+
+    /// Returns a mutable reference to this field for the given [`User`].
+    pub fn value_mut(self, user: &mut User) -> &mut String {
+        match self {
+            Self::FirstName => &mut user.first_name,
+            Self::LastName => &mut user.last_name,
+            Self::Email => &mut user.email,
+            Self::PhoneNumber => &mut user.phone_number,
+        }
+    }
+}
+
+impl Display for Field {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::FirstName => write!(f, "First name"),
+            Self::LastName => write!(f, "Last name"),
+            Self::Email => write!(f, "Email"),
+            Self::PhoneNumber => write!(f, "Phone number"),
+        }
+    }
+}
+
+/// An error describing why a value is not valid for a [`User`] [`Field`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ValidationError {
+    /// The field whose value is invalid.
+    pub field: Field,
+
+    /// Why the value is invalid.
+    pub reason: &'static str,
+}
+
+impl Display for ValidationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}.", self.field, self.reason)
+    }
+}
+
+impl error::Error for ValidationError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests validating the fields of a [`User`].
+    ///
+    /// This test ensures that:
+    /// - Valid values are accepted for every [`Field`].
+    /// - Blank names, malformed emails and phone numbers are rejected.
+    #[test]
+    fn validate_fields() {
+        let mut user = User {
+            first_name: String::from("firstName"),
+            last_name: String::from("firstSurname"),
+            email: String::from("first@email"),
+            phone_number: String::from("+34 012-345-678"),
+        };
+        assert_eq!(user.validate(), Ok(()));
+
+        user.first_name = String::from("  ");
+        assert_eq!(user.validate().unwrap_err().field, Field::FirstName);
+
+        for email in ["first", "@email", "first@", "fi rst@email", "a@b@c"] {
+            assert!(Field::Email.validate(email).is_err(), "{email}");
+        }
+
+        for phone_number in ["", "+", "555-CALL", "12+34"] {
+            assert!(
+                Field::PhoneNumber.validate(phone_number).is_err(),
+                "{phone_number}"
+            );
+        }
+    }
+}